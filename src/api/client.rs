@@ -1,6 +1,6 @@
-use std::{fs, time::Duration};
+use std::fs;
+use std::time::Duration;
 
-use backon::{BlockingRetryable, ExponentialBuilder};
 use log::{debug, info};
 use reqwest::{
     blocking::{self, multipart, Client},
@@ -12,7 +12,8 @@ use crate::{class_hash::ClassHash, errors::RequestFailure};
 
 use super::errors::{ApiClientError, VerificationError};
 use super::models::{
-    Error, FileInfo, ProjectMetadataInfo, VerificationJob, VerificationJobDispatch,
+    ClassInfo, ContractInfo, Error, FileInfo, ProjectMetadataInfo, VerificationJob,
+    VerificationJobDispatch,
 };
 use super::types::VerifyJobStatus;
 
@@ -47,6 +48,20 @@ impl ApiClient {
         }
     }
 
+    /// Like [`ApiClient::new`], but giving up on requests after `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if provided `Url` cannot be a base or the HTTP client can't be
+    /// built.
+    pub fn with_timeout(base: Url, timeout: Duration) -> Result<Self, ApiClientError> {
+        let client = blocking::Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            client,
+            ..Self::new(base)?
+        })
+    }
+
     /// # Errors
     ///
     /// Will return `Err` if the URL cannot be a base.
@@ -59,11 +74,12 @@ impl ApiClient {
         Ok(url)
     }
 
+    /// Look up a class on the explorer, `None` if it isn't declared.
+    ///
     /// # Errors
     ///
-    /// Returns `Err` if the required `class_hash` is not found or on
-    /// network failure.
-    pub fn get_class(&self, class_hash: &ClassHash) -> Result<bool, ApiClientError> {
+    /// Returns `Err` on network failure or unexpected response status.
+    pub fn get_class(&self, class_hash: &ClassHash) -> Result<Option<ClassInfo>, ApiClientError> {
         let url = self.get_class_url(class_hash)?;
         let result = self
             .client
@@ -72,8 +88,53 @@ impl ApiClient {
             .map_err(ApiClientError::from)?;
 
         match result.status() {
-            StatusCode::OK => Ok(true),
-            StatusCode::NOT_FOUND => Ok(false),
+            StatusCode::OK => {
+                let body = result.text()?;
+                // The class exists even if its details can't be parsed
+                let info = serde_json::from_str(&body).unwrap_or_else(|e| {
+                    debug!("Failed to parse class info from {url}: {e}");
+                    ClassInfo::default()
+                });
+                Ok(Some(info))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            _ => Err(ApiClientError::from(RequestFailure::new(
+                url,
+                result.status(),
+                result.text()?,
+            ))),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the URL cannot be a base.
+    pub fn get_contract_url(&self, address: &str) -> Result<Url, ApiClientError> {
+        let mut url = self.base.clone();
+        let url_clone = url.clone();
+        url.path_segments_mut()
+            .map_err(|_| ApiClientError::CannotBeBase(url_clone))?
+            .extend(&["contracts", address]);
+        Ok(url)
+    }
+
+    /// Look up the contract deployed at `address`, `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` on network failure, unexpected response status or if
+    /// the response can't be parsed.
+    pub fn get_contract(&self, address: &str) -> Result<Option<ContractInfo>, ApiClientError> {
+        let url = self.get_contract_url(address)?;
+        let result = self
+            .client
+            .get(url.clone())
+            .send()
+            .map_err(ApiClientError::from)?;
+
+        match result.status() {
+            StatusCode::OK => Ok(Some(result.json()?)),
+            StatusCode::NOT_FOUND => Ok(None),
             _ => Err(ApiClientError::from(RequestFailure::new(
                 url,
                 result.status(),
@@ -106,27 +167,9 @@ impl ApiClient {
         project_metadata: ProjectMetadataInfo,
         files: &[FileInfo],
     ) -> Result<String, ApiClientError> {
-        let mut body = multipart::Form::new()
-            .percent_encode_noop()
-            .text(
-                "compiler_version",
-                project_metadata.cairo_version.to_string(),
-            )
-            .text("scarb_version", project_metadata.scarb_version.to_string())
-            .text("package_name", project_metadata.package_name.clone())
-            .text("name", name.to_string())
-            .text("contract_file", project_metadata.contract_file.clone())
-            .text("contract-name", project_metadata.contract_file.clone())
-            .text(
-                "project_dir_path",
-                project_metadata.project_dir_path.clone(),
-            )
-            .text("build_tool", project_metadata.build_tool.clone());
-
-        // Add Dojo version if available
+        let fields = project_metadata.form_fields(name, license.as_deref());
         if let Some(ref dojo_version) = project_metadata.dojo_version {
             info!("📤 Adding dojo_version to API request: {dojo_version}");
-            body = body.text("dojo_version", dojo_version.clone());
         } else {
             debug!("📤 No dojo_version to include in API request");
         }
@@ -136,18 +179,10 @@ impl ApiClient {
             project_metadata.build_tool, project_metadata.dojo_version
         );
 
-        // Add license using raw SPDX identifier
-        let license_value = if let Some(lic) = license {
-            if lic == "MIT" {
-                "MIT".to_string() // Ensure MIT is formatted correctly
-            } else {
-                lic
-            }
-        } else {
-            "NONE".to_string()
-        };
-
-        body = body.text("license", license_value.clone());
+        let mut body = multipart::Form::new().percent_encode_noop();
+        for (field, value) in &fields {
+            body = body.text(*field, value.clone());
+        }
 
         // Send each file as a separate field with files[] prefix
         for file in files {
@@ -163,20 +198,12 @@ impl ApiClient {
         debug!("🏗️  Request Method: POST");
         debug!("📦 Content-Type: multipart/form-data");
         debug!("📋 === FORM FIELDS ===");
-        debug!("  compiler_version: {}", project_metadata.cairo_version);
-        debug!("  scarb_version: {}", project_metadata.scarb_version);
-        debug!("  package_name: {}", project_metadata.package_name);
-        debug!("  name: {name}");
-        debug!("  contract_file: {}", project_metadata.contract_file);
-        debug!("  contract-name: {}", project_metadata.contract_file);
-        debug!("  project_dir_path: {}", project_metadata.project_dir_path);
-        debug!("  build_tool: {}", project_metadata.build_tool);
-        if let Some(ref dojo_version) = project_metadata.dojo_version {
-            debug!("  dojo_version: {dojo_version}");
-        } else {
+        for (field, value) in &fields {
+            debug!("  {field}: {value}");
+        }
+        if project_metadata.dojo_version.is_none() {
             debug!("  dojo_version: <not included>");
         }
-        debug!("  license: {license_value}");
         debug!("📁 === FILES INCLUDED ===");
         for (index, file) in files.iter().enumerate() {
             let file_size = match fs::metadata(&file.path) {
@@ -241,14 +268,17 @@ impl ApiClient {
         Ok(url)
     }
 
+    /// Fetch the current state of a verification job without interpreting
+    /// its status.
+    ///
     /// # Errors
     ///
-    /// Will return `Err` on network error or if the verification has
-    /// failed.
-    pub fn get_job_status(
+    /// Will return `Err` on network error, if the job doesn't exist or if
+    /// the response can't be parsed.
+    pub fn get_job(
         &self,
         job_id: impl Into<String> + Clone,
-    ) -> Result<JobStatus, ApiClientError> {
+    ) -> Result<VerificationJob, ApiClientError> {
         let url = self.get_job_status_url(job_id.clone().into())?;
         let response = self.client.get(url.clone()).send()?;
 
@@ -281,53 +311,18 @@ impl ApiClient {
         log::debug!("Parsed API Response: job_id={}, status={:?}, status_description={:?}, message={:?}, error_category={:?}", 
                    data.job_id, data.status, data.status_description, data.message, data.error_category);
 
-        match data.status {
-            VerifyJobStatus::Success => Ok(Some(data)),
-            VerifyJobStatus::Fail => {
-                let error_message = data
-                    .message
-                    .or_else(|| data.status_description.clone())
-                    .unwrap_or_else(|| "unknown failure".to_owned());
-
-                // Parse specific error types from the server response
-                let parsed_error = if error_message.contains("Payload too large")
-                    || error_message.contains("payload too large")
-                {
-                    "Request payload too large. The project files exceed the maximum allowed size of 10MB. Try reducing file sizes or removing unnecessary files."
-                } else {
-                    &error_message
-                };
-
-                Err(ApiClientError::from(
-                    VerificationError::VerificationFailure(parsed_error.to_owned()),
-                ))
-            }
-            VerifyJobStatus::CompileFailed => {
-                let error_message = data
-                    .message
-                    .or_else(|| data.status_description.clone())
-                    .unwrap_or_else(|| "unknown failure".to_owned());
-
-                // Parse specific error types from the server response
-                let parsed_error = if error_message.contains("Payload too large")
-                    || error_message.contains("payload too large")
-                {
-                    "Request payload too large. The project files exceed the maximum allowed size of 10MB. Try reducing file sizes or removing unnecessary files."
-                } else if error_message.contains("Couldn't connect to cairo compilation service") {
-                    "Cairo compilation service is currently unavailable. Please try again later."
-                } else {
-                    &error_message
-                };
-
-                Err(ApiClientError::from(VerificationError::CompilationFailure(
-                    parsed_error.to_owned(),
-                )))
-            }
-            VerifyJobStatus::Submitted
-            | VerifyJobStatus::Compiled
-            | VerifyJobStatus::Processing
-            | VerifyJobStatus::Unknown => Ok(None),
-        }
+        Ok(data)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` on network error or if the verification has
+    /// failed.
+    pub fn get_job_status(
+        &self,
+        job_id: impl Into<String> + Clone,
+    ) -> Result<JobStatus, ApiClientError> {
+        into_job_status(self.get_job(job_id)?)
     }
 
     /// # Errors
@@ -341,50 +336,126 @@ impl ApiClient {
     }
 }
 
-pub enum Status {
-    InProgress,
-    Finished(ApiClientError),
-}
+/// Interpret a fetched job: `Some` when it succeeded, `None` while it is
+/// still in progress.
+///
+/// # Errors
+///
+/// Will return `Err` if the compilation or the verification has failed.
+pub(crate) fn into_job_status(data: VerificationJob) -> Result<JobStatus, ApiClientError> {
+    match data.status {
+        VerifyJobStatus::Success => Ok(Some(data)),
+        VerifyJobStatus::Fail => {
+            let error_message = data
+                .message
+                .or_else(|| data.status_description.clone())
+                .unwrap_or_else(|| "unknown failure".to_owned());
+
+            // Parse specific error types from the server response
+            let parsed_error = if error_message.contains("Payload too large")
+                || error_message.contains("payload too large")
+            {
+                "Request payload too large. The project files exceed the maximum allowed size of 10MB. Try reducing file sizes or removing unnecessary files."
+            } else {
+                &error_message
+            };
+
+            Err(ApiClientError::from(
+                VerificationError::VerificationFailure(parsed_error.to_owned()),
+            ))
+        }
+        VerifyJobStatus::CompileFailed => {
+            let error_message = data
+                .message
+                .or_else(|| data.status_description.clone())
+                .unwrap_or_else(|| "unknown failure".to_owned());
+
+            // Parse specific error types from the server response
+            let parsed_error = if error_message.contains("Payload too large")
+                || error_message.contains("payload too large")
+            {
+                "Request payload too large. The project files exceed the maximum allowed size of 10MB. Try reducing file sizes or removing unnecessary files."
+            } else if error_message.contains("Couldn't connect to cairo compilation service") {
+                "Cairo compilation service is currently unavailable. Please try again later."
+            } else {
+                &error_message
+            };
 
-const fn is_is_progress(status: &Status) -> bool {
-    match status {
-        Status::InProgress => true,
-        Status::Finished(_) => false,
+            Err(ApiClientError::from(VerificationError::CompilationFailure(
+                parsed_error.to_owned(),
+            )))
+        }
+        VerifyJobStatus::Submitted
+        | VerifyJobStatus::Compiled
+        | VerifyJobStatus::Processing
+        | VerifyJobStatus::Unknown => Ok(None),
     }
 }
 
-/// # Errors
-///
-/// Will return `Err` on network error or if the verification has
-/// failed.
-pub fn poll_verification_status(
-    api: &ApiClient,
-    job_id: &str,
-) -> Result<VerificationJob, ApiClientError> {
-    let fetch = || -> Result<VerificationJob, Status> {
-        let result: Option<VerificationJob> = api
-            .get_job_status(job_id.to_owned())
-            .map_err(Status::Finished)?;
-
-        result.ok_or(Status::InProgress)
-    };
-
-    // So verbose because it has problems with inference
-    fetch
-        .retry(
-            ExponentialBuilder::default()
-                .with_max_times(0)
-                .with_min_delay(Duration::from_secs(2))
-                .with_max_delay(Duration::from_secs(300)) // 5 mins
-                .with_max_times(20),
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const CLASS_HASH: &str = "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18";
+
+    fn get_class(status: usize, body: &str) -> Option<ClassInfo> {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", format!("/classes/{CLASS_HASH}").as_str())
+            .with_status(status)
+            .with_body(body)
+            .create();
+
+        let api = ApiClient::new(Url::parse(&server.url()).unwrap()).unwrap();
+        api.get_class(&ClassHash::new(CLASS_HASH).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_get_class_not_declared() {
+        assert_eq!(get_class(404, ""), None);
+    }
+
+    #[test]
+    fn test_get_class_verified() {
+        let info = get_class(
+            200,
+            r#"{"hash": "0x44dc", "isVerified": true, "contractName": "Token", "license": "MIT"}"#,
         )
-        .when(is_is_progress)
-        .notify(|_, dur: Duration| {
-            println!("Job: {job_id} didn't finish, retrying in {dur:?}");
-        })
-        .call()
-        .map_err(|err| match err {
-            Status::InProgress => ApiClientError::InProgress,
-            Status::Finished(e) => e,
-        })
+        .unwrap();
+        assert!(info.is_verified());
+        assert_eq!(info.name(), Some("Token"));
+        assert_eq!(info.license(), Some("MIT"));
+    }
+
+    #[test]
+    fn test_get_contract() {
+        let mut server = mockito::Server::new();
+        let _found = server
+            .mock("GET", "/contracts/0xabc")
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"address": "0xabc", "classHash": "{CLASS_HASH}"}}"#
+            ))
+            .create();
+        let _missing = server
+            .mock("GET", "/contracts/0xdef")
+            .with_status(404)
+            .create();
+
+        let api = ApiClient::new(Url::parse(&server.url()).unwrap()).unwrap();
+        let contract = api.get_contract("0xabc").unwrap().unwrap();
+        assert_eq!(
+            contract.class_hash,
+            Some(ClassHash::new(CLASS_HASH).unwrap())
+        );
+        assert_eq!(api.get_contract("0xdef").unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_class_without_details() {
+        let info = get_class(200, "<html></html>").unwrap();
+        assert!(!info.is_verified());
+        assert_eq!(info, ClassInfo::default());
+    }
 }
@@ -1,9 +1,11 @@
+use std::time::Duration;
+
 use thiserror::Error;
 use url::Url;
 
 use crate::errors::RequestFailure;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum VerificationError {
     #[error("[E004] Compilation failed: {0}")]
     CompilationFailure(String),
@@ -87,6 +89,12 @@ pub enum ApiClientError {
 
     #[error("[E009] Invalid URL format: {0}\n\nSuggestions:\n  • Check the URL format is correct\n  • Ensure proper encoding of special characters\n  • Use absolute URLs with protocol (http:// or https://)")]
     UrlCannotBeBase(#[from] url::ParseError),
+
+    #[error("[E038] Job '{job_id}' did not finish within {elapsed:.0?}\n\nSuggestions:\n  • The job keeps running on the server, check it later with 'voyager status --job {job_id}'\n  • Increase the deadline with --timeout <SECS> (0 waits indefinitely)")]
+    Timeout { job_id: String, elapsed: Duration },
+
+    #[error("[E039] Polling of job '{0}' was cancelled\n\nSuggestions:\n  • The job keeps running on the server, check it later with 'voyager status --job {0}'")]
+    Cancelled(String),
 }
 
 impl ApiClientError {
@@ -99,6 +107,8 @@ impl ApiClientError {
             Self::JobNotFound(_) => "E008",
             Self::Verify(v) => v.error_code(),
             Self::UrlCannotBeBase(_) => "E009",
+            Self::Timeout { .. } => "E038",
+            Self::Cancelled(_) => "E039",
         }
     }
 }
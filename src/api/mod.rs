@@ -8,7 +8,7 @@
 //!
 //! - **HTTP Client**: Built on `reqwest` with automatic retries and error handling
 //! - **Type Safety**: Strong typing for all requests and responses
-//! - **Polling**: Automatic polling for long-running verification jobs
+//! - **Polling**: Configurable, cancellable polling for long-running verification jobs
 //! - **Error Handling**: Comprehensive error types with actionable suggestions
 //! - **Multipart Uploads**: Support for uploading contract source files
 //!
@@ -24,9 +24,9 @@
 //! // Create API client
 //! let client = ApiClient::new(Url::parse("https://api.voyager.online/beta")?)?;
 //!
-//! // Check if a class exists
+//! // Check if a class exists and whether it is already verified
 //! let class_hash = ClassHash::new("0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18")?;
-//! let exists = client.get_class(&class_hash)?;
+//! let verified = client.get_class(&class_hash)?.is_some_and(|class| class.is_verified());
 //!
 //! // Get verification job status
 //! let job_status = client.get_job_status("job-id")?;
@@ -38,8 +38,14 @@
 pub use self::{
     client::ApiClient,
     errors::{ApiClientError, VerificationError},
-    models::{FileInfo, ProjectMetadataInfo, VerificationJob, VerificationJobDispatch},
-    polling::poll_verification_status,
+    models::{
+        ClassInfo, ContractInfo, FileInfo, ProjectMetadataInfo, VerificationJob,
+        VerificationJobDispatch,
+    },
+    polling::{
+        poll_verification_status, CancellationToken, Poller, StatusUpdate, DEFAULT_INTERVAL,
+        DEFAULT_TIMEOUT,
+    },
     types::{JobStatus, Status, VerifyJobStatus},
 };
 
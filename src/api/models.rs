@@ -1,7 +1,8 @@
 use super::types::VerifyJobStatus;
+use crate::class_hash::ClassHash;
 use crate::project::ProjectType;
 use semver;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Deserialize)]
@@ -14,7 +15,7 @@ pub struct VerificationJobDispatch {
     pub job_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VerificationJob {
     pub job_id: String,
     pub status: VerifyJobStatus,
@@ -109,6 +110,55 @@ impl VerificationJob {
     }
 }
 
+/// Class as known to the explorer, returned by `GET /classes/{hash}`.
+///
+/// Every field is optional so that a class is still recognised as declared
+/// when the API omits the verification details.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClassInfo {
+    #[serde(default, alias = "hash", alias = "classHash")]
+    pub class_hash: Option<String>,
+    #[serde(default, alias = "is_verified", alias = "isVerified")]
+    pub verified: bool,
+    /// Name of the verified contract
+    #[serde(default, alias = "contract_name", alias = "contractName")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Version of the Cairo compiler of the class, when the explorer reports it
+    #[serde(default, alias = "compilerVersion")]
+    pub compiler_version: Option<String>,
+}
+
+impl ClassInfo {
+    pub const fn is_verified(&self) -> bool {
+        self.verified
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
+    pub fn compiler_version(&self) -> Option<semver::Version> {
+        self.compiler_version
+            .as_deref()
+            .and_then(|version| semver::Version::parse(version).ok())
+    }
+}
+
+/// Contract instance as returned by the explorer API
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ContractInfo {
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default, alias = "classHash")]
+    pub class_hash: Option<ClassHash>,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct FileInfo {
     pub name: String,
@@ -124,6 +174,8 @@ pub struct ProjectMetadataInfo {
     pub package_name: String,
     pub build_tool: String,           // "scarb" or "sozo"
     pub dojo_version: Option<String>, // Dojo version for Dojo projects
+    /// Deployed instance of the class, when verifying by address
+    pub address: Option<String>,
 }
 
 impl ProjectMetadataInfo {
@@ -150,6 +202,37 @@ impl ProjectMetadataInfo {
                 "scarb".to_string()
             },
             dojo_version,
+            address: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_address(mut self, address: Option<String>) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Form fields submitted alongside the files, in the order they are sent
+    #[must_use]
+    pub fn form_fields(&self, name: &str, license: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("compiler_version", self.cairo_version.to_string()),
+            ("scarb_version", self.scarb_version.to_string()),
+            ("package_name", self.package_name.clone()),
+            ("name", name.to_string()),
+            ("contract_file", self.contract_file.clone()),
+            ("contract-name", self.contract_file.clone()),
+            ("project_dir_path", self.project_dir_path.clone()),
+            ("build_tool", self.build_tool.clone()),
+        ];
+        if let Some(ref dojo_version) = self.dojo_version {
+            fields.push(("dojo_version", dojo_version.clone()));
+        }
+        if let Some(ref address) = self.address {
+            fields.push(("address", address.clone()));
         }
+        // Raw SPDX identifier
+        fields.push(("license", license.unwrap_or("NONE").to_string()));
+        fields
     }
 }
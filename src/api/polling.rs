@@ -1,3 +1,343 @@
-// This module contains polling functionality that was moved to the client.rs file
-// Re-export from client for backward compatibility
-pub use super::client::poll_verification_status;
+//! Polling of verification jobs until they reach a final state.
+//!
+//! [`Poller`] repeatedly fetches a job with a fixed interval (optionally
+//! randomized with jitter), reports every status transition to a progress
+//! callback and gives up once the overall deadline passes or the poll is
+//! cancelled through a [`CancellationToken`].
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::client::{into_job_status, ApiClient};
+use super::errors::ApiClientError;
+use super::models::VerificationJob;
+use super::types::VerifyJobStatus;
+
+/// Default delay between two status requests
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default overall deadline for a job to finish
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Upper bound on a single sleep so cancellation is noticed promptly
+const SLEEP_SLICE: Duration = Duration::from_millis(100);
+
+/// Shared flag used to stop a running [`Poller`] from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the poller to stop at the next opportunity.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Passed to the progress callback whenever the job status changes.
+#[derive(Debug)]
+pub struct StatusUpdate<'a> {
+    /// Job as returned by the latest status request
+    pub job: &'a VerificationJob,
+    /// Status observed before this update, `None` for the first one
+    pub previous: Option<&'a VerifyJobStatus>,
+    /// Time since polling started
+    pub elapsed: Duration,
+}
+
+type StatusCallback<'a> = Box<dyn FnMut(&StatusUpdate<'_>) + 'a>;
+
+/// Polls a verification job until it succeeds, fails, times out or is
+/// cancelled.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use verifier::api::{ApiClient, Poller};
+/// use url::Url;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ApiClient::new(Url::parse("https://api.voyager.online/beta")?)?;
+/// let job = Poller::new(&client)
+///     .with_interval(Duration::from_secs(2))
+///     .with_timeout(Some(Duration::from_secs(600)))
+///     .on_status(|update| println!("{}", update.job.status()))
+///     .poll("job-id")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Poller<'a> {
+    api: &'a ApiClient,
+    interval: Duration,
+    timeout: Option<Duration>,
+    jitter: f64,
+    on_status: Option<StatusCallback<'a>>,
+    cancellation: CancellationToken,
+}
+
+impl<'a> Poller<'a> {
+    #[must_use]
+    pub fn new(api: &'a ApiClient) -> Self {
+        Self {
+            api,
+            interval: DEFAULT_INTERVAL,
+            timeout: Some(DEFAULT_TIMEOUT),
+            jitter: 0.0,
+            on_status: None,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Delay between two status requests.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Overall deadline, `None` polls until the job finishes.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Randomize every delay by up to `fraction` of the interval in either
+    /// direction. The value is clamped to `0.0..=1.0`.
+    #[must_use]
+    pub const fn with_jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Call `callback` on the first observed status and on every change.
+    #[must_use]
+    pub fn on_status(mut self, callback: impl FnMut(&StatusUpdate<'_>) + 'a) -> Self {
+        self.on_status = Some(Box::new(callback));
+        self
+    }
+
+    /// Stop polling once `token` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Poll `job_id` until it reaches a final state.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` on network error, if the verification has failed,
+    /// if the deadline passes or if polling was cancelled.
+    pub fn poll(&mut self, job_id: &str) -> Result<VerificationJob, ApiClientError> {
+        let start = Instant::now();
+        let deadline = self.timeout.map(|timeout| start + timeout);
+        let mut previous: Option<VerifyJobStatus> = None;
+
+        loop {
+            self.check_cancelled(job_id)?;
+
+            let job = self.api.get_job(job_id)?;
+            if previous.as_ref() != Some(job.status()) {
+                if let Some(callback) = self.on_status.as_mut() {
+                    callback(&StatusUpdate {
+                        job: &job,
+                        previous: previous.as_ref(),
+                        elapsed: start.elapsed(),
+                    });
+                }
+                previous = Some(job.status().clone());
+            }
+
+            if let Some(job) = into_job_status(job)? {
+                return Ok(job);
+            }
+
+            let delay = self.next_delay();
+            if let Some(deadline) = deadline {
+                if Instant::now() + delay > deadline {
+                    return Err(ApiClientError::Timeout {
+                        job_id: job_id.to_string(),
+                        elapsed: start.elapsed(),
+                    });
+                }
+            }
+            self.sleep(job_id, delay)?;
+        }
+    }
+
+    fn next_delay(&self) -> Duration {
+        if self.jitter == 0.0 {
+            return self.interval;
+        }
+        let factor = (fastrand::f64() * 2.0 - 1.0).mul_add(self.jitter, 1.0);
+        self.interval.mul_f64(factor)
+    }
+
+    fn sleep(&self, job_id: &str, delay: Duration) -> Result<(), ApiClientError> {
+        let wake_up = Instant::now() + delay;
+        loop {
+            self.check_cancelled(job_id)?;
+            let remaining = wake_up.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            thread::sleep(remaining.min(SLEEP_SLICE));
+        }
+    }
+
+    fn check_cancelled(&self, job_id: &str) -> Result<(), ApiClientError> {
+        if self.cancellation.is_cancelled() {
+            Err(ApiClientError::Cancelled(job_id.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Poll with the default interval and deadline.
+///
+/// # Errors
+///
+/// Will return `Err` on network error, if the verification has failed or
+/// if the job doesn't finish before the default deadline.
+pub fn poll_verification_status(
+    api: &ApiClient,
+    job_id: &str,
+) -> Result<VerificationJob, ApiClientError> {
+    Poller::new(api).poll(job_id)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    fn job_body(status: u8) -> String {
+        format!(
+            r#"{{"job_id": "job", "status": {status}, "status_description": null, "class_hash": "0x123"}}"#
+        )
+    }
+
+    fn client(server: &mockito::Server) -> ApiClient {
+        ApiClient::new(Url::parse(&server.url()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_reports_each_transition() {
+        let mut server = mockito::Server::new();
+        let path = "/class-verify/job/job";
+        // Submitted twice, then Compiled, then Success
+        let mocks = [(0, 2), (1, 1), (4, 1)].map(|(status, hits)| {
+            server
+                .mock("GET", path)
+                .with_body(job_body(status))
+                .expect(hits)
+                .create()
+        });
+
+        let api = client(&server);
+        let mut transitions = Vec::new();
+        let job = Poller::new(&api)
+            .with_interval(Duration::from_millis(1))
+            .on_status(|update| {
+                transitions.push((update.previous.cloned(), update.job.status().clone()));
+            })
+            .poll("job")
+            .unwrap();
+
+        assert_eq!(job.status(), &VerifyJobStatus::Success);
+        assert_eq!(
+            transitions,
+            vec![
+                (None, VerifyJobStatus::Submitted),
+                (Some(VerifyJobStatus::Submitted), VerifyJobStatus::Compiled),
+                (Some(VerifyJobStatus::Compiled), VerifyJobStatus::Success),
+            ]
+        );
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[test]
+    fn test_failure_is_returned() {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/class-verify/job/job")
+            .with_body(job_body(3))
+            .create();
+
+        let api = client(&server);
+        let error = Poller::new(&api).poll("job").unwrap_err();
+        assert_eq!(error.error_code(), "E005");
+    }
+
+    #[test]
+    fn test_times_out() {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/class-verify/job/job")
+            .with_body(job_body(5))
+            .create();
+
+        let api = client(&server);
+        let error = Poller::new(&api)
+            .with_interval(Duration::from_millis(20))
+            .with_timeout(Some(Duration::from_millis(50)))
+            .poll("job")
+            .unwrap_err();
+        assert!(matches!(error, ApiClientError::Timeout { .. }));
+        assert_eq!(error.error_code(), "E038");
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/class-verify/job/job")
+            .with_body(job_body(0))
+            .create();
+
+        let api = client(&server);
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let error = Poller::new(&api)
+            .with_interval(Duration::from_secs(60))
+            .with_timeout(None)
+            .with_cancellation(token)
+            .on_status(move |_| canceller.cancel())
+            .poll("job")
+            .unwrap_err();
+        assert_eq!(error.error_code(), "E039");
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let server = mockito::Server::new();
+        let api = client(&server);
+        let poller = Poller::new(&api)
+            .with_interval(Duration::from_secs(10))
+            .with_jitter(0.5);
+        for _ in 0..100 {
+            let delay = poller.next_delay();
+            assert!(delay >= Duration::from_secs(5) && delay <= Duration::from_secs(15));
+        }
+    }
+}
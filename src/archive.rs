@@ -0,0 +1,312 @@
+//! Export of a verification payload for review before it is published.
+//!
+//! The archive holds every file that would be submitted under the same
+//! relative name it is uploaded with, plus a `metadata.json` with the form
+//! fields sent alongside them.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::{write::GzEncoder, Compression};
+use log::debug;
+use serde::{Serialize, Serializer};
+use std::{fs, io::Write};
+use thiserror::Error;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::api::{FileInfo, ProjectMetadataInfo};
+
+/// Name of the entry holding the submission form fields
+pub const METADATA_FILE: &str = "metadata.json";
+
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("[E041] Unsupported archive format: '{0}'\n\nSuggestions:\n  • Use a path ending in .tar.gz, .tgz or .zip")]
+    UnsupportedFormat(Utf8PathBuf),
+
+    #[error("[E042] Failed to write archive '{path}': {error}\n\nSuggestions:\n  • Check that the target directory exists and is writable\n  • Ensure there is enough disk space available")]
+    Write { path: Utf8PathBuf, error: String },
+
+    #[error("[E062] Payload file '{METADATA_FILE}' collides with the archive metadata entry\n\nSuggestions:\n  • Rename the file, or leave it out of the payload\n  • Drop --output-archive, the name is only reserved inside the archive")]
+    ReservedName,
+}
+
+impl ArchiveError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::UnsupportedFormat(_) => "E041",
+            Self::Write { .. } => "E042",
+            Self::ReservedName => "E062",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Pick the format from the file extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the extension is neither `.tar.gz`, `.tgz` nor `.zip`.
+    pub fn from_path(path: &Utf8Path) -> Result<Self, ArchiveError> {
+        let name = path.file_name().unwrap_or_default().to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Self::TarGz)
+        } else if name.ends_with(".zip") {
+            Ok(Self::Zip)
+        } else {
+            Err(ArchiveError::UnsupportedFormat(path.to_path_buf()))
+        }
+    }
+}
+
+/// Form fields sent with the files, as stored in `metadata.json`
+#[derive(Debug, Clone)]
+pub struct PayloadMetadata {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl PayloadMetadata {
+    #[must_use]
+    pub fn new(name: &str, project_metadata: &ProjectMetadataInfo, license: Option<&str>) -> Self {
+        Self {
+            fields: project_metadata.form_fields(name, license),
+        }
+    }
+}
+
+impl Serialize for PayloadMetadata {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Kept in submission order rather than sorted
+        serializer.collect_map(self.fields.iter().map(|(field, value)| (field, value)))
+    }
+}
+
+/// Write `files` and `metadata` to `path`, in the format given by its
+/// extension.
+///
+/// # Errors
+///
+/// Returns `Err` if the format is unsupported, a payload file can't be read
+/// or the archive can't be written.
+pub fn write_payload(
+    path: &Utf8Path,
+    files: &[FileInfo],
+    metadata: &PayloadMetadata,
+) -> Result<(), ArchiveError> {
+    let format = ArchiveFormat::from_path(path)?;
+    let write_error = |error: String| ArchiveError::Write {
+        path: path.to_path_buf(),
+        error,
+    };
+
+    if files.iter().any(|file| file.name == METADATA_FILE) {
+        return Err(ArchiveError::ReservedName);
+    }
+
+    let metadata = serde_json::to_vec_pretty(metadata).map_err(|e| write_error(e.to_string()))?;
+
+    // Sorted so that the same payload always produces the same archive
+    let mut entries = files
+        .iter()
+        .map(|file| {
+            fs::read(&file.path)
+                .map(|contents| (file.name.as_str(), contents))
+                .map_err(|e| write_error(format!("{}: {e}", file.path.display())))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries.push((METADATA_FILE, metadata));
+
+    let output = fs::File::create(path).map_err(|e| write_error(e.to_string()))?;
+    match format {
+        ArchiveFormat::TarGz => write_tar_gz(output, &entries),
+        ArchiveFormat::Zip => write_zip(output, &entries),
+    }
+    .map_err(write_error)?;
+
+    debug!("Wrote {} entries to {path}", entries.len());
+    Ok(())
+}
+
+fn write_tar_gz(output: fs::File, entries: &[(&str, Vec<u8>)]) -> Result<(), String> {
+    let mut builder = tar::Builder::new(GzEncoder::new(output, Compression::default()));
+    for (name, contents) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        builder
+            .append_data(&mut header, name, contents.as_slice())
+            .map_err(|e| format!("{name}: {e}"))?;
+    }
+    builder
+        .into_inner()
+        .and_then(GzEncoder::finish)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn write_zip(output: fs::File, entries: &[(&str, Vec<u8>)]) -> Result<(), String> {
+    let mut writer = ZipWriter::new(output);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .unix_permissions(0o644);
+    for (name, contents) in entries {
+        writer
+            .start_file(*name, options)
+            .map_err(|e| format!("{name}: {e}"))?;
+        writer
+            .write_all(contents)
+            .map_err(|e| format!("{name}: {e}"))?;
+    }
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::project::ProjectType;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn payload(dir: &TempDir) -> (Vec<FileInfo>, PayloadMetadata) {
+        let lib = dir.path().join("lib.cairo");
+        fs::write(&lib, "mod contract;").unwrap();
+        let manifest = dir.path().join("Scarb.toml");
+        fs::write(&manifest, "[package]").unwrap();
+
+        let files = vec![
+            FileInfo {
+                name: "my_project/src/lib.cairo".to_string(),
+                path: lib,
+            },
+            FileInfo {
+                name: "my_project/Scarb.toml".to_string(),
+                path: manifest,
+            },
+        ];
+        let project_metadata = ProjectMetadataInfo::new(
+            semver::Version::new(2, 11, 4),
+            semver::Version::new(2, 11, 4),
+            "my_project".to_string(),
+            "my_project/src/lib.cairo".to_string(),
+            "my_project".to_string(),
+            ProjectType::Scarb,
+            None,
+        );
+        let metadata = PayloadMetadata::new("MyContract", &project_metadata, Some("MIT"));
+        (files, metadata)
+    }
+
+    fn check_metadata(contents: &str) {
+        let value: serde_json::Value = serde_json::from_str(contents).unwrap();
+        assert_eq!(value["compiler_version"], "2.11.4");
+        assert_eq!(value["package_name"], "my_project");
+        assert_eq!(value["build_tool"], "scarb");
+        assert_eq!(value["license"], "MIT");
+        assert_eq!(value["contract-name"], "my_project/src/lib.cairo");
+        assert!(value["dojo_version"].is_null());
+    }
+
+    #[test]
+    fn test_archive_format_from_path() {
+        assert_eq!(
+            ArchiveFormat::from_path(Utf8Path::new("out/payload.tar.gz")).unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Utf8Path::new("payload.TGZ")).unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Utf8Path::new("payload.zip")).unwrap(),
+            ArchiveFormat::Zip
+        );
+        let error = ArchiveFormat::from_path(Utf8Path::new("payload.tar")).unwrap_err();
+        assert_eq!(error.error_code(), "E041");
+    }
+
+    #[test]
+    fn test_write_tar_gz() {
+        let dir = TempDir::new().unwrap();
+        let (files, metadata) = payload(&dir);
+        let path = Utf8PathBuf::try_from(dir.path().join("payload.tar.gz")).unwrap();
+        write_payload(&path, &files, &metadata).unwrap();
+
+        let mut archive =
+            tar::Archive::new(flate2::read::GzDecoder::new(fs::File::open(&path).unwrap()));
+        let mut entries = vec![];
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            entries.push((entry.path().unwrap().display().to_string(), contents));
+        }
+
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "my_project/Scarb.toml",
+                "my_project/src/lib.cairo",
+                METADATA_FILE
+            ]
+        );
+        assert_eq!(entries[1].1, "mod contract;");
+        check_metadata(&entries[2].1);
+    }
+
+    #[test]
+    fn test_write_zip() {
+        let dir = TempDir::new().unwrap();
+        let (files, metadata) = payload(&dir);
+        let path = Utf8PathBuf::try_from(dir.path().join("payload.zip")).unwrap();
+        write_payload(&path, &files, &metadata).unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 3);
+
+        let mut contents = String::new();
+        archive
+            .by_name("my_project/src/lib.cairo")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "mod contract;");
+
+        let mut contents = String::new();
+        archive
+            .by_name(METADATA_FILE)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        check_metadata(&contents);
+    }
+
+    #[test]
+    fn test_metadata_follows_submission_order() {
+        let dir = TempDir::new().unwrap();
+        let (_, metadata) = payload(&dir);
+        let contents = serde_json::to_string(&metadata).unwrap();
+        let position = |field: &str| contents.find(&format!("\"{field}\"")).unwrap();
+        assert!(position("compiler_version") < position("contract-name"));
+        assert!(position("contract-name") < position("license"));
+    }
+
+    #[test]
+    fn test_reject_payload_file_named_like_metadata() {
+        let dir = TempDir::new().unwrap();
+        let (mut files, metadata) = payload(&dir);
+        files[0].name = METADATA_FILE.to_string();
+        let path = Utf8PathBuf::try_from(dir.path().join("payload.zip")).unwrap();
+        let error = write_payload(&path, &files, &metadata).unwrap_err();
+        assert_eq!(error.error_code(), "E062");
+        assert!(!path.exists());
+    }
+}
@@ -1,13 +1,34 @@
 use camino::Utf8PathBuf;
+use clap::{
+    builder::{ArgPredicate, PossibleValuesParser},
+    CommandFactory,
+};
 use lazy_static::lazy_static;
+use log::debug;
 use regex::Regex;
 use reqwest::Url;
 use scarb_metadata::{Metadata, MetadataCommand, MetadataCommandError};
 use spdx::LicenseId;
-use std::{env, fmt::Display, io, path::PathBuf};
+use std::{
+    env,
+    ffi::OsString,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use thiserror::Error;
 
-use verifier::{class_hash::ClassHash, project::ProjectType};
+use verifier::{
+    archive::ArchiveFormat,
+    class_hash::ClassHash,
+    config::{self, ConfigError, Defaults},
+    network::NetworkRegistry,
+    project::ProjectType,
+    prompt::Prompter,
+    resolver,
+    voyager::SourceGlobs,
+};
 
 fn get_name_validation_regex() -> Result<&'static Regex, String> {
     lazy_static! {
@@ -214,17 +235,168 @@ Examples:
     --class-hash 0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18 \\
     --contract-name MyContract
 
+  # Verify every contract listed in [tool.voyager]
+  voyager verify --network mainnet --all
+
   # Check verification status
   voyager status --network mainnet --job job-id-here
 
   # Check status using custom API
   voyager status --url https://api.custom.com/beta --job job-id-here
+
+  # Compute the class hash of a local build artifact
+  voyager hash target/release/my_project_MyContract.contract_class.json
 ")]
 pub struct Args {
+    /// Configuration profile to apply, defined as [profile.<NAME>] in .voyager.toml or ~/.config/voyager/config.toml
+    #[arg(long, global = true, value_name = "NAME", env = "VOYAGER_PROFILE")]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Value of `--<name> VALUE` or `--<name>=VALUE`, read before the command
+/// line is parsed because it selects the configuration to parse it with.
+fn raw_flag_value(argv: &[OsString], name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let prefix = format!("--{name}=");
+    let mut argv = argv.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = argv.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == flag {
+            return argv.next().map(|value| value.to_string());
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Name of the subcommand, read before the command line is parsed to tell
+/// whether it needs the configuration.
+fn raw_subcommand(argv: &[OsString]) -> Option<String> {
+    let mut argv = argv.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = argv.next() {
+        if arg == "--profile" {
+            argv.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg.to_string());
+        }
+    }
+    None
+}
+
+/// Build the command line parser with defaults from the configuration files
+/// of the project selected by `--path` and the profile selected by
+/// `--profile`, along with the networks it accepts.
+///
+/// Subcommands that take no defaults, like `hash`, don't read the
+/// configuration, so a broken file doesn't get in their way.
+pub fn command_with_config(
+    argv: &[OsString],
+) -> Result<(clap::Command, NetworkRegistry), ConfigError> {
+    if !matches!(
+        raw_subcommand(argv).as_deref(),
+        Some("verify" | "status" | "list")
+    ) {
+        return Ok((Args::command(), NetworkRegistry::builtin()));
+    }
+
+    let project_dir = raw_flag_value(argv, "path").map_or_else(
+        || PathBuf::from("."),
+        |path| {
+            let path = PathBuf::from(path);
+            if path.is_file() {
+                path.parent().map(Path::to_path_buf).unwrap_or_default()
+            } else {
+                path
+            }
+        },
+    );
+    let profile = raw_flag_value(argv, "profile").or_else(|| env::var("VOYAGER_PROFILE").ok());
+
+    let defaults = config::load(
+        config::user_config_path().as_deref(),
+        &project_dir,
+        profile.as_deref(),
+    )?;
+    debug!("Configuration defaults: {defaults:?}");
+    let networks = NetworkRegistry::with_config(&defaults.networks)?;
+
+    let command = Args::command()
+        .mut_subcommand("verify", |cmd| {
+            apply_verify_defaults(apply_network_defaults(cmd, &defaults, &networks), &defaults)
+        })
+        .mut_subcommand("status", |cmd| {
+            apply_network_defaults(cmd, &defaults, &networks)
+        })
+        .mut_subcommand("list", |cmd| apply_format_default(cmd, &defaults));
+    Ok((command, networks))
+}
+
+fn apply_network_defaults(
+    mut cmd: clap::Command,
+    defaults: &Defaults,
+    networks: &NetworkRegistry,
+) -> clap::Command {
+    cmd = cmd.mut_arg("network", |arg| {
+        arg.value_parser(PossibleValuesParser::new(networks.names()))
+    });
+
+    let mut url = url_arg(networks);
+    if let Some(network) = &defaults.network {
+        cmd = cmd.mut_arg("network", |arg| {
+            arg.default_value(network.clone()).default_value_if(
+                "url",
+                ArgPredicate::IsPresent,
+                None,
+            )
+        });
+        // An unknown name is reported by the parser through the default above
+        if let Some(entry) = networks.get(network) {
+            url = url.default_value(entry.api_url.to_string());
+        }
+    }
+    if let Some(default_url) = &defaults.url {
+        url = url.default_value(default_url.clone());
+    }
+    if url.get_default_values().is_empty() {
+        url = url.required_unless_present("network");
+    }
+    cmd = cmd.mut_arg("url", |_| url);
+
+    apply_format_default(cmd, defaults)
+}
+
+fn apply_format_default(cmd: clap::Command, defaults: &Defaults) -> clap::Command {
+    match &defaults.format {
+        Some(format) => cmd.mut_arg("format", |arg| arg.default_value(format.clone())),
+        None => cmd,
+    }
+}
+
+fn apply_verify_defaults(mut cmd: clap::Command, defaults: &Defaults) -> clap::Command {
+    let values = [
+        ("license", defaults.license.clone()),
+        ("lock_file", defaults.lock_file.map(|v| v.to_string())),
+        ("test_files", defaults.test_files.map(|v| v.to_string())),
+        ("project_type", defaults.project_type.clone()),
+        ("watch", defaults.watch.map(|v| v.to_string())),
+        ("cairo_version", defaults.cairo_version.clone()),
+        ("scarb_version", defaults.scarb_version.clone()),
+    ];
+    for (id, value) in values {
+        if let Some(value) = value {
+            cmd = cmd.mut_arg(id, |arg| arg.default_value(value));
+        }
+    }
+    cmd
+}
+
 #[derive(clap::Subcommand)]
 #[allow(clippy::large_enum_variant)]
 pub enum Commands {
@@ -249,6 +421,14 @@ pub enum Commands {
     ///   voyager verify --url <https://api.custom.com/beta> \
     ///     --class-hash 0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18 \
     ///     --contract-name `MyContract`
+    ///
+    ///   # Verifying every contract from [tool.voyager]
+    ///   voyager verify --network mainnet --all
+    ///
+    ///   # Showing why each file is or isn't part of the payload
+    ///   voyager verify --network mainnet --dry-run --explain \
+    ///     --class-hash 0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18 \
+    ///     --contract-name `MyContract`
     Verify(VerifyArgs),
 
     /// Check the status of a verification job
@@ -266,6 +446,29 @@ pub enum Commands {
     ///   # Using custom API endpoint
     ///   voyager status --url <https://api.custom.com/beta> --job 12345678-1234-1234-1234-123456789012
     Status(StatusArgs),
+
+    /// Compute the class hash of a compiled contract class
+    ///
+    /// Computes the Sierra class hash of a `*.contract_class.json` artifact
+    /// produced by `scarb build` locally, without contacting any network.
+    ///
+    /// Examples:
+    ///   voyager hash `target/release/my_project_MyContract.contract_class.json`
+    Hash(HashArgs),
+
+    /// List the contracts that can be verified
+    ///
+    /// Prints every package of the project with its starknet-contract
+    /// targets and the #[starknet::contract] and #[dojo::contract] modules
+    /// found in its sources. When the project is built, the class hash of
+    /// each contract is computed from its artifact in target/<profile>/.
+    ///
+    /// Examples:
+    ///   voyager list
+    ///
+    ///   # Feeding the contracts into verification
+    ///   voyager list --format json | jq -r '.packages[].contracts[] | "\(.name) \(.class_hash)"'
+    List(ListArgs),
 }
 
 fn license_value_parser(license: &str) -> Result<LicenseId, String> {
@@ -344,6 +547,38 @@ fn contract_name_value_parser(name: &str) -> Result<String, String> {
     Ok(name.to_string())
 }
 
+fn archive_value_parser(path: &str) -> Result<Utf8PathBuf, String> {
+    let path = Utf8PathBuf::from(path);
+    ArchiveFormat::from_path(&path)
+        .map_err(|_| "Archive must be a .tar.gz, .tgz or .zip file".to_string())?;
+    Ok(path)
+}
+
+fn address_value_parser(address: &str) -> Result<String, String> {
+    let digits = address
+        .strip_prefix("0x")
+        .ok_or_else(|| "Contract address must start with 0x".to_string())?;
+    if digits.is_empty() || digits.len() > 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(
+            "Contract address must be 0x followed by 1 to 64 hexadecimal digits".to_string(),
+        );
+    }
+    Ok(address.to_string())
+}
+
+fn version_value_parser(version: &str) -> Result<semver::Version, String> {
+    semver::Version::parse(version)
+        .map_err(|e| format!("Invalid version '{version}', expected e.g. 2.11.4: {e}"))
+}
+
+fn glob_value_parser(pattern: &str) -> Result<String, String> {
+    resolver::compile_glob(pattern).map_err(|e| match e {
+        resolver::Error::InvalidGlob { error, .. } => format!("Invalid glob: {error}"),
+        e => e.to_string(),
+    })?;
+    Ok(pattern.to_string())
+}
+
 fn package_name_value_parser(name: &str) -> Result<String, String> {
     // Check for minimum length
     if name.is_empty() {
@@ -369,13 +604,28 @@ fn package_name_value_parser(name: &str) -> Result<String, String> {
 
 #[derive(clap::Args)]
 pub struct VerifyArgs {
-    /// Network to verify on (mainnet, sepolia, dev). If not specified, --url is required
-    #[arg(long, value_enum)]
-    pub network: Option<NetworkKind>,
+    /// Network to verify on (mainnet, sepolia, dev or one from [networks.<NAME>] in the configuration). If not specified, --url is required
+    #[arg(
+        long,
+        value_name = "NAME",
+        env = "VOYAGER_NETWORK",
+        value_parser = PossibleValuesParser::new(NetworkRegistry::BUILTIN)
+    )]
+    pub network: Option<String>,
 
     #[command(flatten)]
     pub network_url: Network,
 
+    /// Starknet JSON-RPC endpoint used to check the class on chain (default: rpc-url of the network)
+    #[arg(
+        long = "rpc-url",
+        value_name = "URL",
+        env = "VOYAGER_RPC_URL",
+        value_hint = clap::ValueHint::Url,
+        value_parser = Url::parse
+    )]
+    pub rpc_url: Option<Url>,
+
     /// Perform dry run (preview what would be submitted without sending)
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
@@ -394,19 +644,49 @@ pub struct VerifyArgs {
     #[arg(
         long = "class-hash",
         value_name = "HASH",
-        value_parser = ClassHash::new
+        value_parser = ClassHash::new,
+        required_unless_present_any = ["all", "address"]
     )]
-    pub class_hash: ClassHash,
+    pub class_hash: Option<ClassHash>,
 
-    /// Wait indefinitely for verification result (polls until completion)
-    #[arg(long, default_value_t = false)]
+    /// Address of a deployed contract whose class to verify, instead of --class-hash
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        value_parser = address_value_parser,
+        conflicts_with = "all"
+    )]
+    pub address: Option<String>,
+
+    /// Wait for verification result (polls until completion or --timeout)
+    #[arg(long, default_value_t = false, env = "VOYAGER_WATCH")]
     pub watch: bool,
 
+    /// Don't wait for the verification result, even if watch is enabled in the configuration or environment
+    #[arg(long)]
+    pub no_watch: bool,
+
+    /// With --watch, if verification fails, build the payload locally and compare it with the class declared on chain (needs an RPC endpoint)
+    #[arg(long, default_value_t = false, conflicts_with = "all")]
+    pub diagnose: bool,
+
+    /// Never prompt; choices fall back to a default or fail. Implied in CI and when stdin is not a terminal
+    #[arg(long, default_value_t = false, env = "VOYAGER_NON_INTERACTIVE")]
+    pub non_interactive: bool,
+
+    #[command(flatten)]
+    pub poll: PollArgs,
+
+    /// Output format; json prints a single machine-readable report on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, env = "VOYAGER_FORMAT")]
+    pub format: OutputFormat,
+
     /// SPDX license identifier (e.g., MIT, Apache-2.0)
     #[arg(
         long,
         value_name = "SPDX",
         value_parser = license_value_parser,
+        env = "VOYAGER_LICENSE"
     )]
     pub license: Option<LicenseId>,
 
@@ -414,11 +694,20 @@ pub struct VerifyArgs {
     #[arg(
         long = "contract-name",
         value_name = "NAME",
-        value_parser = contract_name_value_parser
+        value_parser = contract_name_value_parser,
+        required_unless_present = "all"
+    )]
+    pub contract_name: Option<String>,
+
+    /// Verify every contract listed in the [tool.voyager] section of each workspace member
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["class_hash", "contract_name"]
     )]
-    pub contract_name: String,
+    pub all: bool,
 
-    /// Select specific package for verification (required for workspace projects)
+    /// Package defining the contract (default: the workspace member defining --contract-name)
     #[arg(
         long,
         value_name = "PACKAGE_ID",
@@ -427,28 +716,138 @@ pub struct VerifyArgs {
     pub package: Option<String>,
 
     /// Include Scarb.lock file in verification submission
-    #[arg(long, default_value_t = false)]
+    #[arg(long, default_value_t = false, env = "VOYAGER_LOCK_FILE")]
     pub lock_file: bool,
 
+    /// Leave Scarb.lock out, even if lock-file is enabled in the configuration or environment
+    #[arg(long)]
+    pub no_lock_file: bool,
+
     /// Include test files from src/ directory in verification submission
-    #[arg(long, default_value_t = false)]
+    #[arg(long, default_value_t = false, env = "VOYAGER_TEST_FILES")]
     pub test_files: bool,
 
+    /// Leave test files out, even if test-files is enabled in the configuration or environment
+    #[arg(long)]
+    pub no_test_files: bool,
+
+    /// Glob of files to submit even if excluded by default, relative to each package root (repeatable)
+    #[arg(long, value_name = "GLOB", value_parser = glob_value_parser)]
+    pub include: Vec<String>,
+
+    /// Glob of files to never submit, relative to each package root (repeatable)
+    #[arg(long, value_name = "GLOB", value_parser = glob_value_parser)]
+    pub exclude: Vec<String>,
+
+    /// Build the payload locally and check it matches --class-hash before submitting
+    #[arg(long, default_value_t = false)]
+    pub precheck: bool,
+
+    /// Write the payload of a dry run to a .tar.gz or .zip archive for review
+    #[arg(
+        long = "output-archive",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        value_parser = archive_value_parser,
+        requires = "dry_run",
+        conflicts_with = "all"
+    )]
+    pub output_archive: Option<Utf8PathBuf>,
+
+    /// List every candidate file of a dry run with the reason it was included or excluded
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "dry_run",
+        conflicts_with = "all"
+    )]
+    pub explain: bool,
+
+    /// Submit even if the class is already verified
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
+
+    /// Submit even if the local Cairo version differs from the compiler of the declared class
+    #[arg(long = "allow-toolchain-mismatch", default_value_t = false)]
+    pub allow_toolchain_mismatch: bool,
+
+    /// Cairo version to submit instead of the one of the local Scarb installation
+    #[arg(
+        long = "cairo-version",
+        value_name = "VERSION",
+        value_parser = version_value_parser,
+        env = "VOYAGER_CAIRO_VERSION"
+    )]
+    pub cairo_version: Option<semver::Version>,
+
+    /// Scarb version to submit instead of the one installed locally
+    #[arg(
+        long = "scarb-version",
+        value_name = "VERSION",
+        value_parser = version_value_parser,
+        env = "VOYAGER_SCARB_VERSION"
+    )]
+    pub scarb_version: Option<semver::Version>,
+
     /// Project type for build tool selection
     #[arg(
         long = "project-type",
         value_enum,
         default_value_t = ProjectType::Auto,
+        env = "VOYAGER_PROJECT_TYPE",
         help = "Specify the project type (scarb, dojo, or auto-detect)"
     )]
     pub project_type: ProjectType,
 }
 
+impl VerifyArgs {
+    /// Globs given with --include and --exclude
+    #[must_use]
+    pub fn source_globs(&self) -> SourceGlobs {
+        SourceGlobs {
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+        }
+    }
+
+    /// Prompter honouring --non-interactive and the environment
+    #[must_use]
+    pub fn prompter(&self) -> Prompter {
+        Prompter::detect(self.non_interactive)
+    }
+
+    /// Whether to wait for the result; --no-watch wins over --watch and
+    /// its defaults
+    #[must_use]
+    pub const fn watch(&self) -> bool {
+        self.watch && !self.no_watch
+    }
+
+    /// Whether to submit Scarb.lock; --no-lock-file wins over --lock-file
+    /// and its defaults
+    #[must_use]
+    pub const fn lock_file(&self) -> bool {
+        self.lock_file && !self.no_lock_file
+    }
+
+    /// Whether to submit test files; --no-test-files wins over
+    /// --test-files and its defaults
+    #[must_use]
+    pub const fn test_files(&self) -> bool {
+        self.test_files && !self.no_test_files
+    }
+}
+
 #[derive(clap::Args)]
 pub struct StatusArgs {
-    /// Network to verify on (mainnet, sepolia, dev). If not specified, --url is required
-    #[arg(long, value_enum)]
-    pub network: Option<NetworkKind>,
+    /// Network to verify on (mainnet, sepolia, dev or one from [networks.<NAME>] in the configuration). If not specified, --url is required
+    #[arg(
+        long,
+        value_name = "NAME",
+        env = "VOYAGER_NETWORK",
+        value_parser = PossibleValuesParser::new(NetworkRegistry::BUILTIN)
+    )]
+    pub network: Option<String>,
 
     #[command(flatten)]
     pub network_url: Network,
@@ -456,18 +855,78 @@ pub struct StatusArgs {
     /// Verification job ID (UUID format)
     #[arg(long, value_name = "UUID")]
     pub job: String,
+
+    #[command(flatten)]
+    pub poll: PollArgs,
+
+    /// Output format; json prints a single machine-readable report on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, env = "VOYAGER_FORMAT")]
+    pub format: OutputFormat,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable output
+    Text,
+
+    /// JSON report for scripts and CI pipelines
+    Json,
+}
+
+#[derive(clap::Args)]
+pub struct PollArgs {
+    /// Give up waiting for the job after this many seconds (0 waits indefinitely)
+    #[arg(long, value_name = "SECS", default_value_t = 1800)]
+    pub timeout: u64,
+
+    /// Seconds between two job status requests
+    #[arg(
+        long = "poll-interval",
+        value_name = "SECS",
+        default_value_t = 5,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub poll_interval: u64,
 }
 
-#[derive(clap::ValueEnum, Clone)]
-pub enum NetworkKind {
-    /// Target the Mainnet
-    Mainnet,
+impl PollArgs {
+    #[must_use]
+    pub const fn timeout(&self) -> Option<Duration> {
+        if self.timeout == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(self.timeout))
+        }
+    }
+
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval)
+    }
+}
+
+#[derive(clap::Args)]
+pub struct ListArgs {
+    /// Path to Scarb project directory (default: current directory)
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = clap::ValueHint::DirPath,
+        value_parser = project_value_parser,
+        default_value = "."
+    )]
+    pub path: Project,
 
-    /// Target Sepolia testnet
-    Sepolia,
+    /// Output format; json prints a single machine-readable report on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, env = "VOYAGER_FORMAT")]
+    pub format: OutputFormat,
+}
 
-    /// Target the development network
-    Dev,
+#[derive(clap::Args)]
+pub struct HashArgs {
+    /// Path to the *.contract_class.json artifact
+    #[arg(value_name = "ARTIFACT", value_hint = clap::ValueHint::FilePath)]
+    pub artifact: Utf8PathBuf,
 }
 
 #[derive(Clone)]
@@ -518,45 +977,97 @@ impl clap::FromArgMatches for Network {
     }
 }
 
+/// `--url`, defaulting to the API of the network selected with
+/// `--network`. It isn't marked as required, as a configured default may
+/// satisfy it.
+fn url_arg(networks: &NetworkRegistry) -> clap::Arg {
+    clap::Arg::new("url")
+        .long("url")
+        .env("VOYAGER_URL")
+        .help("API endpoint URL (required when --network is not specified)")
+        .value_hint(clap::ValueHint::Url)
+        .value_parser(Url::parse)
+        .default_value_ifs(
+            networks
+                .iter()
+                .map(|network| ("network", network.name.clone(), network.api_url.to_string())),
+        )
+}
+
 // Can't derive the default value logic, hence hand rolled instance
 impl clap::Args for Network {
     fn augment_args(cmd: clap::Command) -> clap::Command {
-        cmd.arg(
-            clap::Arg::new("url")
-                .long("url")
-                .help("API endpoint URL (required when --network is not specified)")
-                .value_hint(clap::ValueHint::Url)
-                .value_parser(Url::parse)
-                .default_value_ifs([
-                    ("network", "mainnet", "https://api.voyager.online/beta"),
-                    (
-                        "network",
-                        "sepolia",
-                        "https://sepolia-api.voyager.online/beta",
-                    ),
-                    ("network", "dev", "https://dev-api.voyager.online/beta"),
-                ])
-                .required_unless_present("network"),
-        )
+        cmd.arg(url_arg(&NetworkRegistry::builtin()).required_unless_present("network"))
     }
 
     fn augment_args_for_update(cmd: clap::Command) -> clap::Command {
-        cmd.arg(
-            clap::Arg::new("url")
-                .long("url")
-                .help("API endpoint URL (required when --network is not specified)")
-                .value_hint(clap::ValueHint::Url)
-                .value_parser(Url::parse)
-                .default_value_ifs([
-                    ("network", "mainnet", "https://api.voyager.online/beta"),
-                    (
-                        "network",
-                        "sepolia",
-                        "https://sepolia-api.voyager.online/beta",
-                    ),
-                    ("network", "dev", "https://dev-api.voyager.online/beta"),
-                ])
-                .required_unless_present("network"),
-        )
+        cmd.arg(url_arg(&NetworkRegistry::builtin()).required_unless_present("network"))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn os_args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    /// Parse `verify` flags with `defaults` applied, without resolving a project
+    fn verify_matches(defaults: &Defaults, flags: &[&str]) -> clap::ArgMatches {
+        let verify = Args::command().find_subcommand("verify").unwrap().clone();
+        let mut argv = vec![
+            "verify",
+            "--url",
+            "https://api.voyager.online/beta",
+            "--class-hash",
+            "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18",
+            "--contract-name",
+            "MyContract",
+        ];
+        argv.extend(flags);
+        apply_verify_defaults(verify, defaults)
+            .mut_arg("path", |arg| arg.value_parser(clap::value_parser!(String)))
+            .try_get_matches_from(argv)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_raw_subcommand() {
+        assert_eq!(
+            raw_subcommand(&os_args(&["voyager", "hash", "artifact.json"])).as_deref(),
+            Some("hash")
+        );
+        assert_eq!(
+            raw_subcommand(&os_args(&["voyager", "--profile", "ci", "status"])).as_deref(),
+            Some("status")
+        );
+        assert_eq!(raw_subcommand(&os_args(&["voyager", "--help"])), None);
+    }
+
+    #[test]
+    fn test_negated_flags_next_to_configuration() {
+        let defaults = Defaults {
+            watch: Some(true),
+            lock_file: Some(true),
+            test_files: Some(true),
+            ..Defaults::default()
+        };
+
+        let matches = verify_matches(&defaults, &[]);
+        for id in ["watch", "lock_file", "test_files"] {
+            assert!(matches.get_flag(id));
+            assert!(!matches.get_flag(&format!("no_{id}")));
+        }
+
+        // VerifyArgs::watch() and the like let the negation win
+        let matches = verify_matches(
+            &defaults,
+            &["--watch", "--no-watch", "--no-lock-file", "--no-test-files"],
+        );
+        for id in ["no_watch", "no_lock_file", "no_test_files"] {
+            assert!(matches.get_flag(id));
+        }
     }
 }
@@ -9,6 +9,7 @@
 //! - **Validation**: Automatic validation of format and length
 //! - **Performance**: Compiled regex patterns for efficient validation
 //! - **Error Handling**: Detailed error messages with actionable suggestions
+//! - **Local Hashing**: Compute Sierra class hashes from Scarb build artifacts
 //!
 //! ## Example Usage
 //!
@@ -25,8 +26,16 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
+pub use self::sierra::{
+    compute_class_hash, find_artifact, find_artifact_in, ArtifactError, ContractClass,
+    EntryPointsByType, SierraEntryPoint,
+};
+
+mod sierra;
+
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::Deserialize;
 use std::fmt;
 use thiserror::Error;
 
@@ -71,7 +80,8 @@ fn get_class_hash_regex() -> Result<&'static Regex, ClassHashError> {
 /// assert!(ClassHash::new("0xGGG").is_err());
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
 pub struct ClassHash(String);
 
 /// Errors that can occur when validating or creating class hashes.
@@ -141,6 +151,38 @@ impl ClassHash {
             Err(ClassHashError::Match(raw.to_string()))
         }
     }
+
+    /// Returns the hash in canonical form: lowercase and zero-padded to
+    /// 64 hexadecimal characters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use verifier::class_hash::ClassHash;
+    ///
+    /// let hash = ClassHash::new("0x44DC")?;
+    /// assert_eq!(hash.normalized(), format!("0x{:0>64}", "44dc"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn normalized(&self) -> String {
+        let digits = self.0.trim_start_matches("0x").to_lowercase();
+        format!("0x{digits:0>64}")
+    }
+
+    /// Compares two class hashes ignoring case and leading zeros.
+    #[must_use]
+    pub fn matches(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl TryFrom<String> for ClassHash {
+    type Error = ClassHashError;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        Self::new(&raw)
+    }
 }
 
 impl fmt::Display for ClassHash {
@@ -234,6 +276,30 @@ mod tests {
         assert_eq!(class_hash, cloned);
     }
 
+    #[test]
+    fn test_class_hash_matches_ignores_padding_and_case() {
+        let padded =
+            ClassHash::new("0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18")
+                .unwrap();
+        let short =
+            ClassHash::new("0x44DC2B3239382230D8B1E943DF23B96F52EEBCAC93EFE6E8BDE92F9A2F1DA18")
+                .unwrap();
+        assert!(padded.matches(&short));
+        assert_eq!(padded.normalized(), padded.to_string());
+
+        let other = ClassHash::new("0x123").unwrap();
+        assert!(!padded.matches(&other));
+    }
+
+    #[test]
+    fn test_class_hash_deserialize() {
+        let hash = "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18";
+        let class_hash: ClassHash = serde_json::from_str(&format!("\"{hash}\"")).unwrap();
+        assert_eq!(class_hash.as_ref() as &str, hash);
+
+        assert!(serde_json::from_str::<ClassHash>("\"invalid_hash\"").is_err());
+    }
+
     #[test]
     fn test_class_hash_error_display() {
         let error = ClassHashError::Match("invalid_hash".to_string());
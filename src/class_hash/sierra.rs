@@ -0,0 +1,377 @@
+//! Offline computation of Sierra class hashes from Scarb build artifacts.
+//!
+//! The hash follows the Starknet definition for `CONTRACT_CLASS_V0.1.0`:
+//!
+//! ```text
+//! poseidon(
+//!     "CONTRACT_CLASS_V0.1.0",
+//!     poseidon(external entry points),
+//!     poseidon(l1 handler entry points),
+//!     poseidon(constructor entry points),
+//!     starknet_keccak(abi),
+//!     poseidon(sierra program),
+//! )
+//! ```
+//!
+//! The ABI is hashed in the same Python-compatible JSON form that tooling
+//! uses when declaring a class, so the result matches the declared hash.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use scarb_metadata::{Metadata, PackageMetadata};
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use starknet_types_core::{
+    felt::Felt,
+    hash::{Poseidon, StarkHash},
+};
+use std::{fs, io};
+use thiserror::Error;
+
+use super::ClassHash;
+
+const CONTRACT_CLASS_VERSION_PREFIX: &[u8] = b"CONTRACT_CLASS_V0.1.0";
+
+/// Errors that can occur when reading an artifact or hashing its contents.
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    #[error("[E030] Failed to read contract class artifact '{path}': {error}\n\nSuggestions:\n  • Check that the file exists and is readable\n  • Run 'scarb build' to produce the artifact\n  • Artifacts are written to target/<profile>/*.contract_class.json")]
+    Io { path: Utf8PathBuf, error: String },
+
+    #[error("[E031] Failed to parse contract class artifact '{path}': {error}\n\nSuggestions:\n  • Make sure the file is a Sierra *.contract_class.json artifact\n  • CASM (*.compiled_contract_class.json) artifacts are not supported\n  • Rebuild the project with 'scarb build'")]
+    Parse { path: Utf8PathBuf, error: String },
+
+    #[error("[E032] Invalid field element '{value}' in contract class\n\nSuggestions:\n  • The artifact may be corrupted, rebuild it with 'scarb build'\n  • Field elements must be hexadecimal strings prefixed with '0x'")]
+    InvalidFelt { value: String },
+}
+
+impl ArtifactError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::Io { .. } => "E030",
+            Self::Parse { .. } => "E031",
+            Self::InvalidFelt { .. } => "E032",
+        }
+    }
+}
+
+/// Sierra entry point as listed in `entry_points_by_type`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SierraEntryPoint {
+    pub selector: String,
+    pub function_idx: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EntryPointsByType {
+    #[serde(rename = "EXTERNAL", default)]
+    pub external: Vec<SierraEntryPoint>,
+    #[serde(rename = "L1_HANDLER", default)]
+    pub l1_handler: Vec<SierraEntryPoint>,
+    #[serde(rename = "CONSTRUCTOR", default)]
+    pub constructor: Vec<SierraEntryPoint>,
+}
+
+/// Sierra contract class, either a Scarb `*.contract_class.json` artifact
+/// or a declared class where the ABI is already a flattened string.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContractClass {
+    pub sierra_program: Vec<String>,
+    pub contract_class_version: String,
+    pub entry_points_by_type: EntryPointsByType,
+    #[serde(default)]
+    pub abi: serde_json::Value,
+}
+
+impl ContractClass {
+    /// Read and parse a `*.contract_class.json` artifact.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the file can't be read or isn't a Sierra contract class.
+    pub fn from_file(path: &Utf8Path) -> Result<Self, ArtifactError> {
+        let contents = fs::read_to_string(path).map_err(|e| ArtifactError::Io {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        })?;
+
+        serde_json::from_str(&contents).map_err(|e| ArtifactError::Parse {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        })
+    }
+
+    /// The ABI in the form it is hashed and declared on chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the ABI can't be serialized.
+    pub fn abi_string(&self) -> Result<String, ArtifactError> {
+        match &self.abi {
+            serde_json::Value::String(flattened) => Ok(flattened.clone()),
+            abi => to_string_pythonic(abi).map_err(|e| ArtifactError::Parse {
+                path: Utf8PathBuf::from("abi"),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    /// Compute the Sierra class hash of this contract class.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the program or a selector isn't a valid field element.
+    pub fn class_hash(&self) -> Result<ClassHash, ArtifactError> {
+        let program = self
+            .sierra_program
+            .iter()
+            .map(|felt| parse_felt(felt))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let hash = Poseidon::hash_array(&[
+            Felt::from_bytes_be_slice(CONTRACT_CLASS_VERSION_PREFIX),
+            hash_entry_points(&self.entry_points_by_type.external)?,
+            hash_entry_points(&self.entry_points_by_type.l1_handler)?,
+            hash_entry_points(&self.entry_points_by_type.constructor)?,
+            starknet_keccak(self.abi_string()?.as_bytes()),
+            Poseidon::hash_array(&program),
+        ]);
+
+        Ok(ClassHash(hash.to_fixed_hex_string()))
+    }
+
+    /// Sierra version of the program, read from its header.
+    #[must_use]
+    pub fn sierra_version(&self) -> Option<semver::Version> {
+        self.header_version(0)
+    }
+
+    /// Version of the Cairo compiler that produced the program, read from
+    /// its header.
+    #[must_use]
+    pub fn compiler_version(&self) -> Option<semver::Version> {
+        self.header_version(3)
+    }
+
+    /// Version encoded as three felts at `offset` of the program header
+    fn header_version(&self, offset: usize) -> Option<semver::Version> {
+        let part = |index: usize| {
+            let felt = self.sierra_program.get(offset + index)?;
+            u64::from_str_radix(felt.trim_start_matches("0x"), 16).ok()
+        };
+        Some(semver::Version::new(part(0)?, part(1)?, part(2)?))
+    }
+}
+
+/// Compute the class hash of the `*.contract_class.json` artifact at `path`.
+///
+/// # Errors
+///
+/// Returns `Err` if the artifact can't be read, parsed or hashed.
+pub fn compute_class_hash(path: &Utf8Path) -> Result<ClassHash, ArtifactError> {
+    ContractClass::from_file(path)?.class_hash()
+}
+
+/// Locate the Scarb build artifact of `contract_name` within `package`.
+///
+/// Looks in `target/<profile>` for the current profile first, then in
+/// `release` and `dev`.
+#[must_use]
+pub fn find_artifact(
+    metadata: &Metadata,
+    package: &PackageMetadata,
+    contract_name: &str,
+) -> Option<Utf8PathBuf> {
+    let target_dir = metadata
+        .target_dir
+        .clone()
+        .unwrap_or_else(|| metadata.workspace.root.join("target"));
+
+    let mut profiles = vec![metadata.current_profile.as_str()];
+    for profile in ["release", "dev"] {
+        if !profiles.contains(&profile) {
+            profiles.push(profile);
+        }
+    }
+
+    profiles
+        .iter()
+        .find_map(|profile| find_artifact_in(&target_dir.join(profile), package, contract_name))
+}
+
+/// Locate the build artifact of `contract_name` within a single profile
+/// output directory such as `target/release`.
+#[must_use]
+pub fn find_artifact_in(
+    profile_dir: &Utf8Path,
+    package: &PackageMetadata,
+    contract_name: &str,
+) -> Option<Utf8PathBuf> {
+    package
+        .targets
+        .iter()
+        .filter(|target| target.kind == "starknet-contract")
+        .map(|target| target.name.as_str())
+        .chain(std::iter::once(package.name.as_str()))
+        .map(|target_name| {
+            profile_dir.join(format!("{target_name}_{contract_name}.contract_class.json"))
+        })
+        .find(|path| path.exists())
+}
+
+fn parse_felt(value: &str) -> Result<Felt, ArtifactError> {
+    Felt::from_hex(value).map_err(|_| ArtifactError::InvalidFelt {
+        value: value.to_string(),
+    })
+}
+
+fn hash_entry_points(entry_points: &[SierraEntryPoint]) -> Result<Felt, ArtifactError> {
+    let mut felts = Vec::with_capacity(entry_points.len() * 2);
+    for entry_point in entry_points {
+        felts.push(parse_felt(&entry_point.selector)?);
+        felts.push(Felt::from(entry_point.function_idx));
+    }
+    Ok(Poseidon::hash_array(&felts))
+}
+
+/// Keccak256 truncated to the 250 bits that fit in a field element.
+fn starknet_keccak(data: &[u8]) -> Felt {
+    let mut hash: [u8; 32] = Keccak256::digest(data).into();
+    hash[0] &= 0x03;
+    Felt::from_bytes_be(&hash)
+}
+
+/// Serialize like Python's `json.dumps` with default settings, which is how
+/// the ABI string of a declared class is produced.
+fn to_string_pythonic(value: &serde_json::Value) -> Result<String, serde_json::Error> {
+    let mut buffer = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, PythonicFormatter);
+    serde::Serialize::serialize(value, &mut serializer)?;
+    // The formatter only ever emits ASCII
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+struct PythonicFormatter;
+
+impl serde_json::ser::Formatter for PythonicFormatter {
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(b": ")
+    }
+
+    fn write_string_fragment<W>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        // Python escapes everything outside of ASCII by default
+        for c in fragment.chars() {
+            if c.is_ascii() {
+                writer.write_all(&[c as u8])?;
+            } else {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    write!(writer, "\\u{unit:04x}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/hello_starknet.contract_class.json"
+    );
+
+    #[test]
+    fn test_class_hash_of_artifact() {
+        let class_hash = compute_class_hash(Utf8Path::new(FIXTURE)).unwrap();
+        assert_eq!(
+            class_hash.to_string(),
+            "0x0523e006fd59721c46bdb1955859fe3abb57201ef27cb6e483a079166f845c5c"
+        );
+    }
+
+    #[test]
+    fn test_program_header_versions() {
+        let class = ContractClass::from_file(Utf8Path::new(FIXTURE)).unwrap();
+        assert_eq!(class.sierra_version(), Some(semver::Version::new(1, 6, 0)));
+        assert_eq!(
+            class.compiler_version(),
+            Some(semver::Version::new(2, 11, 4))
+        );
+    }
+
+    #[test]
+    fn test_flattened_abi_hashes_the_same() {
+        let mut class = ContractClass::from_file(Utf8Path::new(FIXTURE)).unwrap();
+        let expected = class.class_hash().unwrap();
+
+        class.abi = serde_json::Value::String(class.abi_string().unwrap());
+        assert_eq!(class.class_hash().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_pythonic_serialization() {
+        let value = serde_json::json!({"type": "function", "name": "caf\u{e9}", "inputs": [1, 2]});
+        assert_eq!(
+            to_string_pythonic(&value).unwrap(),
+            r#"{"type": "function", "name": "caf\u00e9", "inputs": [1, 2]}"#
+        );
+    }
+
+    #[test]
+    fn test_starknet_keccak() {
+        // Selector of `transfer`
+        assert_eq!(
+            starknet_keccak(b"transfer"),
+            Felt::from_hex("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_felt() {
+        let mut class = ContractClass::from_file(Utf8Path::new(FIXTURE)).unwrap();
+        class.sierra_program.push("not-a-felt".to_string());
+
+        let error = class.class_hash().unwrap_err();
+        assert_eq!(error.error_code(), "E032");
+        assert!(format!("{error}").contains("not-a-felt"));
+    }
+
+    #[test]
+    fn test_missing_artifact() {
+        let error =
+            compute_class_hash(Utf8Path::new("/nonexistent.contract_class.json")).unwrap_err();
+        assert_eq!(error.error_code(), "E030");
+    }
+}
@@ -0,0 +1,318 @@
+//! Default values for command line arguments, read from configuration files.
+//!
+//! Three layers are merged, later ones overriding earlier ones:
+//!
+//! 1. the user configuration, `~/.config/voyager/config.toml`
+//! 2. the `[tool.voyager.defaults]` section of the project's `Scarb.toml`
+//! 3. the project configuration, `.voyager.toml` next to `Scarb.toml`
+//!
+//! Each layer may define named profiles under `[profile.<name>]`. When a
+//! profile is selected, its settings from every layer are applied on top of
+//! the merged base settings. Arguments given on the command line or through
+//! environment variables always take precedence over configuration files.
+
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use crate::network::{NetworkConfig, NetworkError};
+
+/// Project configuration file, looked up next to `Scarb.toml`
+pub const PROJECT_CONFIG: &str = ".voyager.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("[E045] Failed to read configuration file '{path}': {error}\n\nSuggestions:\n  • Check that the file is readable\n  • Verify file permissions")]
+    Read { path: PathBuf, error: String },
+
+    #[error("[E046] Invalid configuration in '{path}': {error}\n\nSuggestions:\n  • Check TOML syntax is valid\n  • Supported keys: network, url, license, lock-file, test-files, project-type, watch, format, cairo-version, scarb-version\n  • Networks are defined as [networks.<name>] tables with api-url, explorer-url-template, rpc-url and max-payload-size\n  • Profiles are defined as [profile.<name>] tables")]
+    Parse { path: PathBuf, error: String },
+
+    #[error("[E047] Profile '{name}' is not defined\n\nSuggestions:\n  • Define it as [profile.{name}] in .voyager.toml or ~/.config/voyager/config.toml\n  • Available profiles: {}", if available.is_empty() { "none".to_string() } else { available.join(", ") })]
+    UnknownProfile {
+        name: String,
+        available: Vec<String>,
+    },
+
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+}
+
+impl ConfigError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::Read { .. } => "E045",
+            Self::Parse { .. } => "E046",
+            Self::UnknownProfile { .. } => "E047",
+            Self::Network(e) => e.error_code(),
+        }
+    }
+}
+
+/// Defaults for `verify` and `status` arguments. Values are kept as strings
+/// and validated by the command line parser, like values typed by the user.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Defaults {
+    /// Predefined network name, mutually exclusive with `url`
+    pub network: Option<String>,
+    /// Custom API endpoint, mutually exclusive with `network`
+    pub url: Option<String>,
+    pub license: Option<String>,
+    pub lock_file: Option<bool>,
+    pub test_files: Option<bool>,
+    pub project_type: Option<String>,
+    pub watch: Option<bool>,
+    pub format: Option<String>,
+    /// Cairo version submitted instead of the local one
+    pub cairo_version: Option<String>,
+    /// Scarb version submitted instead of the local one
+    pub scarb_version: Option<String>,
+    /// Networks added to or overriding the built-in ones
+    #[serde(default)]
+    pub networks: BTreeMap<String, NetworkConfig>,
+    #[serde(default)]
+    pub profile: BTreeMap<String, Defaults>,
+}
+
+impl Defaults {
+    /// Override the values of `self` with those set in `other`. Profiles
+    /// aren't merged.
+    pub fn merge(&mut self, other: &Self) {
+        // A layer choosing a network or a URL replaces both
+        if other.network.is_some() || other.url.is_some() {
+            self.network.clone_from(&other.network);
+            self.url.clone_from(&other.url);
+        }
+        merge_option(&mut self.license, &other.license);
+        merge_option(&mut self.lock_file, &other.lock_file);
+        merge_option(&mut self.test_files, &other.test_files);
+        merge_option(&mut self.project_type, &other.project_type);
+        merge_option(&mut self.watch, &other.watch);
+        merge_option(&mut self.format, &other.format);
+        merge_option(&mut self.cairo_version, &other.cairo_version);
+        merge_option(&mut self.scarb_version, &other.scarb_version);
+        for (name, network) in &other.networks {
+            self.networks
+                .entry(name.clone())
+                .or_default()
+                .merge(network);
+        }
+    }
+}
+
+fn merge_option<T: Clone>(value: &mut Option<T>, other: &Option<T>) {
+    if other.is_some() {
+        value.clone_from(other);
+    }
+}
+
+/// Path of the user configuration file, `$XDG_CONFIG_HOME/voyager/config.toml`
+/// or `~/.config/voyager/config.toml`.
+#[must_use]
+pub fn user_config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("voyager").join("config.toml"))
+}
+
+/// Load and merge the configuration layers for the project in
+/// `project_dir`, applying `profile` if given. Missing files are skipped.
+///
+/// # Errors
+///
+/// Returns `Err` if a file can't be read or parsed, or if `profile` isn't
+/// defined in any layer.
+pub fn load(
+    user_config: Option<&Path>,
+    project_dir: &Path,
+    profile: Option<&str>,
+) -> Result<Defaults, ConfigError> {
+    let mut layers = vec![];
+    if let Some(path) = user_config {
+        layers.extend(read_config(path)?);
+    }
+    layers.extend(read_manifest_defaults(&project_dir.join("Scarb.toml"))?);
+    layers.extend(read_config(&project_dir.join(PROJECT_CONFIG))?);
+
+    let mut defaults = Defaults::default();
+    for layer in &layers {
+        defaults.merge(layer);
+    }
+
+    if let Some(name) = profile {
+        let selected: Vec<&Defaults> = layers
+            .iter()
+            .filter_map(|layer| layer.profile.get(name))
+            .collect();
+        if selected.is_empty() {
+            let mut available: Vec<String> = layers
+                .iter()
+                .flat_map(|layer| layer.profile.keys().cloned())
+                .collect();
+            available.sort();
+            available.dedup();
+            return Err(ConfigError::UnknownProfile {
+                name: name.to_string(),
+                available,
+            });
+        }
+        for layer in selected {
+            defaults.merge(layer);
+        }
+    }
+
+    Ok(defaults)
+}
+
+fn read_to_string(path: &Path) -> Result<Option<String>, ConfigError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .map_err(|e| ConfigError::Read {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        })
+}
+
+fn read_config(path: &Path) -> Result<Option<Defaults>, ConfigError> {
+    let Some(contents) = read_to_string(path)? else {
+        return Ok(None);
+    };
+    toml::from_str(&contents)
+        .map(Some)
+        .map_err(|e| ConfigError::Parse {
+            path: path.to_path_buf(),
+            error: e.message().to_string(),
+        })
+}
+
+fn read_manifest_defaults(path: &Path) -> Result<Option<Defaults>, ConfigError> {
+    let Some(contents) = read_to_string(path)? else {
+        return Ok(None);
+    };
+    let parse_error = |error: String| ConfigError::Parse {
+        path: path.to_path_buf(),
+        error,
+    };
+
+    let manifest: toml::Value =
+        toml::from_str(&contents).map_err(|e| parse_error(e.message().to_string()))?;
+    let Some(section) = manifest
+        .get("tool")
+        .and_then(|tool| tool.get("voyager"))
+        .and_then(|voyager| voyager.get("defaults"))
+    else {
+        return Ok(None);
+    };
+    section
+        .clone()
+        .try_into()
+        .map(Some)
+        .map_err(|e: toml::de::Error| {
+            parse_error(format!("[tool.voyager.defaults]: {}", e.message()))
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project(scarb_toml: &str, voyager_toml: Option<&str>) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Scarb.toml"), scarb_toml).unwrap();
+        if let Some(contents) = voyager_toml {
+            fs::write(dir.path().join(PROJECT_CONFIG), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_layers_are_merged_in_order() {
+        let user = TempDir::new().unwrap();
+        let user_config = user.path().join("config.toml");
+        fs::write(
+            &user_config,
+            "network = \"sepolia\"\nlicense = \"MIT\"\nwatch = true\n",
+        )
+        .unwrap();
+
+        let dir = project(
+            "[package]\nname = \"app\"\n\n[tool.voyager.defaults]\nlicense = \"Apache-2.0\"\nlock-file = true\n",
+            Some("url = \"https://api.custom.com/beta\"\nformat = \"json\"\nscarb-version = \"2.10.1\"\n\n[networks.devnet]\napi-url = \"http://127.0.0.1:5050\"\n"),
+        );
+
+        let defaults = load(Some(&user_config), dir.path(), None).unwrap();
+        assert_eq!(defaults.network, None);
+        assert_eq!(defaults.url.as_deref(), Some("https://api.custom.com/beta"));
+        assert_eq!(defaults.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(defaults.lock_file, Some(true));
+        assert_eq!(defaults.watch, Some(true));
+        assert_eq!(defaults.format.as_deref(), Some("json"));
+        assert_eq!(defaults.test_files, None);
+        assert_eq!(defaults.scarb_version.as_deref(), Some("2.10.1"));
+        assert_eq!(defaults.cairo_version, None);
+        assert_eq!(
+            defaults.networks["devnet"].api_url.as_deref(),
+            Some("http://127.0.0.1:5050")
+        );
+    }
+
+    #[test]
+    fn test_profile_overrides_every_layer() {
+        let dir = project(
+            "[package]\nname = \"app\"\n\n[tool.voyager.defaults.profile.ci]\nformat = \"json\"\n",
+            Some("network = \"sepolia\"\nwatch = true\n\n[profile.ci]\nnetwork = \"mainnet\"\nwatch = false\n"),
+        );
+
+        let defaults = load(None, dir.path(), None).unwrap();
+        assert_eq!(defaults.network.as_deref(), Some("sepolia"));
+        assert_eq!(defaults.format, None);
+
+        let defaults = load(None, dir.path(), Some("ci")).unwrap();
+        assert_eq!(defaults.network.as_deref(), Some("mainnet"));
+        assert_eq!(defaults.watch, Some(false));
+        assert_eq!(defaults.format.as_deref(), Some("json"));
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let dir = project(
+            "[package]\nname = \"app\"\n",
+            Some("[profile.ci]\nwatch = true\n"),
+        );
+
+        let error = load(None, dir.path(), Some("release")).unwrap_err();
+        assert_eq!(error.error_code(), "E047");
+        assert!(error.to_string().contains("Available profiles: ci"));
+    }
+
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        let dir = project(
+            "[package]\nname = \"app\"\n",
+            Some("netwrok = \"mainnet\"\n"),
+        );
+
+        let error = load(None, dir.path(), None).unwrap_err();
+        assert_eq!(error.error_code(), "E046");
+        assert!(error.to_string().contains("netwrok"));
+    }
+
+    #[test]
+    fn test_missing_files_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        let defaults = load(Some(&dir.path().join("missing.toml")), dir.path(), None).unwrap();
+        assert_eq!(defaults, Defaults::default());
+    }
+}
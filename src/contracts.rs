@@ -0,0 +1,221 @@
+//! Discovery of the contracts defined in Cairo sources.
+//!
+//! A contract is an inline module carrying `#[starknet::contract]` or
+//! `#[dojo::contract]`. Contracts are found by scanning the collected source
+//! files of a package, without compiling it.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use scarb_metadata::PackageMetadata;
+use serde::Serialize;
+use std::{fmt, fs};
+
+use crate::resolver::{self, parse_cairo_modules};
+
+const CAIRO_EXT: &str = "cairo";
+
+/// Attribute marking a module as a contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractKind {
+    /// `#[starknet::contract]`
+    Starknet,
+    /// `#[dojo::contract]`
+    Dojo,
+}
+
+impl ContractKind {
+    fn from_attribute(attribute: &str) -> Option<Self> {
+        let path = attribute
+            .trim_start_matches("#[")
+            .trim_end_matches(']')
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        match path {
+            "starknet::contract" => Some(Self::Starknet),
+            "dojo::contract" => Some(Self::Dojo),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ContractKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Starknet => write!(f, "#[starknet::contract]"),
+            Self::Dojo => write!(f, "#[dojo::contract]"),
+        }
+    }
+}
+
+/// A contract module found in the sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractModule {
+    /// Module name, which is the contract name
+    pub name: String,
+    /// Full path of the module, e.g. `my_package::token::Token`
+    pub module_path: String,
+    /// Source file defining the module
+    pub file: Utf8PathBuf,
+    pub kind: ContractKind,
+}
+
+/// Find the contract modules defined in the `sources` of `package`, in the
+/// order of `sources`.
+///
+/// # Errors
+///
+/// Returns `Err` if one of the package's Cairo sources can't be read.
+pub fn find_contracts(
+    package: &PackageMetadata,
+    sources: &[Utf8PathBuf],
+) -> Result<Vec<ContractModule>, resolver::Error> {
+    let root = crate_root(package);
+    let mut contracts = vec![];
+    for file in sources {
+        if !file.starts_with(&package.root) || file.extension() != Some(CAIRO_EXT) {
+            continue;
+        }
+        let content = fs::read_to_string(file).map_err(|e| resolver::Error::IoError {
+            path: file.to_string(),
+            error: e.to_string(),
+        })?;
+
+        let file_module = file_module_path(&package.name, root.as_deref(), file);
+        contracts.extend(scan_source(&content, &file_module, file));
+    }
+    Ok(contracts)
+}
+
+/// Contract modules defined in `content`, the source of `file` whose module
+/// path is `file_module`.
+fn scan_source(content: &str, file_module: &str, file: &Utf8Path) -> Vec<ContractModule> {
+    parse_cairo_modules(content)
+        .into_iter()
+        .filter(|module| module.inline)
+        .filter_map(|module| {
+            let kind = module
+                .attributes
+                .iter()
+                .find_map(|attribute| ContractKind::from_attribute(attribute))?;
+            Some(ContractModule {
+                module_path: format!("{file_module}::{}", module.path()),
+                name: module.name,
+                file: file.to_path_buf(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Source file of the first non-test target, usually `src/lib.cairo`
+fn crate_root(package: &PackageMetadata) -> Option<Utf8PathBuf> {
+    package
+        .targets
+        .iter()
+        .find(|target| target.kind != "test")
+        .map(|target| target.source_path.clone())
+}
+
+/// Module path of the file at `file`, `src/token/erc20.cairo` being
+/// `package::token::erc20` for a crate rooted at `src/lib.cairo`.
+fn file_module_path(package_name: &str, root: Option<&Utf8Path>, file: &Utf8Path) -> String {
+    if root == Some(file) {
+        return package_name.to_string();
+    }
+    let mut path = vec![package_name.to_string()];
+    let relative = root
+        .and_then(Utf8Path::parent)
+        .and_then(|dir| file.strip_prefix(dir).ok())
+        .unwrap_or(file);
+    path.extend(
+        relative
+            .with_extension("")
+            .components()
+            .map(|component| component.as_str().to_string()),
+    );
+    path.join("::")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_kind_from_attribute() {
+        assert_eq!(
+            ContractKind::from_attribute("#[starknet::contract]"),
+            Some(ContractKind::Starknet)
+        );
+        assert_eq!(
+            ContractKind::from_attribute("#[starknet::contract(account)]"),
+            Some(ContractKind::Starknet)
+        );
+        assert_eq!(
+            ContractKind::from_attribute("#[dojo::contract]"),
+            Some(ContractKind::Dojo)
+        );
+        assert_eq!(ContractKind::from_attribute("#[starknet::interface]"), None);
+        assert_eq!(ContractKind::from_attribute("#[cfg(test)]"), None);
+    }
+
+    #[test]
+    fn test_scan_source() {
+        let content = r"
+#[starknet::interface]
+pub trait ICounter<T> {
+    fn get(self: @T) -> u32;
+}
+
+#[starknet::contract]
+pub mod Counter {
+    #[storage]
+    struct Storage {}
+}
+
+mod helpers {
+    #[dojo::contract]
+    mod actions {}
+
+    // #[starknet::contract]
+    mod not_a_contract {}
+}
+
+#[starknet::contract]
+mod external;
+";
+        let file = Utf8Path::new("/p/src/counter.cairo");
+        let contracts = scan_source(content, "app::counter", file);
+        assert_eq!(
+            contracts
+                .iter()
+                .map(|contract| (
+                    contract.name.as_str(),
+                    contract.module_path.as_str(),
+                    contract.kind
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Counter", "app::counter::Counter", ContractKind::Starknet),
+                (
+                    "actions",
+                    "app::counter::helpers::actions",
+                    ContractKind::Dojo
+                ),
+            ]
+        );
+        assert!(contracts.iter().all(|contract| contract.file == file));
+    }
+
+    #[test]
+    fn test_file_module_path() {
+        let root = Utf8Path::new("/p/src/lib.cairo");
+        assert_eq!(file_module_path("app", Some(root), root), "app");
+        assert_eq!(
+            file_module_path("app", Some(root), Utf8Path::new("/p/src/token/erc20.cairo")),
+            "app::token::erc20"
+        );
+    }
+}
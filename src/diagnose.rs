@@ -0,0 +1,365 @@
+//! Comparison of a local build with the class declared on chain.
+//!
+//! When a verification fails, the class built from the payload is compared
+//! with the declared one part by part: ABI entries, entry point selectors,
+//! `contract_class_version` and the Sierra program. Which parts differ
+//! points at the likely cause, e.g. an identical ABI with a different
+//! program is a compiler version or `sierra-replace-ids` mismatch.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::class_hash::{ContractClass, SierraEntryPoint};
+
+/// Number of felts encoding the Sierra and compiler versions at the start of
+/// a program
+const PROGRAM_HEADER_LENGTH: usize = 6;
+
+/// Entries present on one side only
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SetDiff {
+    /// Only in the local build
+    pub local_only: Vec<String>,
+    /// Only in the declared class
+    pub declared_only: Vec<String>,
+}
+
+impl SetDiff {
+    fn new(local: &BTreeMap<String, String>, declared: &BTreeMap<String, String>) -> Self {
+        // An entry differing in content is listed on both sides
+        let only = |side: &BTreeMap<String, String>, other: &BTreeMap<String, String>| {
+            side.iter()
+                .filter(|(key, value)| other.get(*key) != Some(value))
+                .map(|(key, _)| key.clone())
+                .collect()
+        };
+        Self {
+            local_only: only(local, declared),
+            declared_only: only(declared, local),
+        }
+    }
+
+    fn of_keys(local: &BTreeSet<String>, declared: &BTreeSet<String>) -> Self {
+        Self {
+            local_only: local.difference(declared).cloned().collect(),
+            declared_only: declared.difference(local).cloned().collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.local_only.is_empty() && self.declared_only.is_empty()
+    }
+}
+
+/// A value of the local build and of the declared class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Pair<T> {
+    pub local: T,
+    pub declared: T,
+}
+
+impl<T: PartialEq> Pair<T> {
+    pub fn matches(&self) -> bool {
+        self.local == self.declared
+    }
+}
+
+/// Differences between a local build and the declared class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClassDiff {
+    pub abi: SetDiff,
+    pub external: SetDiff,
+    pub l1_handler: SetDiff,
+    pub constructor: SetDiff,
+    pub contract_class_version: Pair<String>,
+    /// Sierra version from the program header
+    pub sierra_version: Pair<Option<String>>,
+    /// Compiler version from the program header
+    pub compiler_version: Pair<Option<String>>,
+    pub program_length: Pair<usize>,
+    /// Whether the programs are identical, ignoring their headers, and
+    /// entry points call the same functions
+    pub program_matches: bool,
+}
+
+impl ClassDiff {
+    #[must_use]
+    pub fn interface_matches(&self) -> bool {
+        self.abi.is_empty()
+            && self.external.is_empty()
+            && self.l1_handler.is_empty()
+            && self.constructor.is_empty()
+    }
+
+    /// Likely cause of the failure given the differing parts
+    #[must_use]
+    pub fn verdict(&self) -> String {
+        if !self.interface_matches() {
+            return "The interface differs → the sources are not those of the declared contract (check --contract-name, --package and the revision)".to_string();
+        }
+        if !self.compiler_version.matches() || !self.sierra_version.matches() {
+            return format!(
+                "ABI matches, compiled with Cairo {} locally but {} on chain → compiler version mismatch",
+                version_or_unknown(self.compiler_version.local.as_deref()),
+                version_or_unknown(self.compiler_version.declared.as_deref()),
+            );
+        }
+        if !self.contract_class_version.matches() {
+            return "ABI matches, contract_class_version differs → compiler version mismatch"
+                .to_string();
+        }
+        if !self.program_matches {
+            return "ABI matches, Sierra program differs → compiler version or `sierra-replace-ids` mismatch".to_string();
+        }
+        "The local build matches the declared class → the submitted payload builds differently, check that it has every file the build needs (--lock-file, --include)".to_string()
+    }
+}
+
+fn version_or_unknown(version: Option<&str>) -> &str {
+    version.unwrap_or("unknown")
+}
+
+impl fmt::Display for ClassDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = |matches: bool| if matches { "✓" } else { "✗" };
+        let sets = [
+            ("ABI entries", &self.abi),
+            ("External entry points", &self.external),
+            ("L1 handler entry points", &self.l1_handler),
+            ("Constructor entry points", &self.constructor),
+        ];
+        for (title, diff) in sets {
+            writeln!(f, "  {} {title}", mark(diff.is_empty()))?;
+            for entry in &diff.local_only {
+                writeln!(f, "      - local only: {entry}")?;
+            }
+            for entry in &diff.declared_only {
+                writeln!(f, "      - on chain only: {entry}")?;
+            }
+        }
+        writeln!(
+            f,
+            "  {} contract_class_version: {} locally, {} on chain",
+            mark(self.contract_class_version.matches()),
+            self.contract_class_version.local,
+            self.contract_class_version.declared
+        )?;
+        writeln!(
+            f,
+            "  {} Compiler version: {} locally, {} on chain",
+            mark(self.compiler_version.matches()),
+            version_or_unknown(self.compiler_version.local.as_deref()),
+            version_or_unknown(self.compiler_version.declared.as_deref())
+        )?;
+        writeln!(
+            f,
+            "  {} Sierra program: {} felts locally, {} on chain",
+            mark(self.program_matches),
+            self.program_length.local,
+            self.program_length.declared
+        )?;
+        write!(f, "\n  {}", self.verdict())
+    }
+}
+
+/// Compare the `local` build with the `declared` class.
+#[must_use]
+pub fn compare(local: &ContractClass, declared: &ContractClass) -> ClassDiff {
+    let local_entry_points = &local.entry_points_by_type;
+    let declared_entry_points = &declared.entry_points_by_type;
+    ClassDiff {
+        abi: SetDiff::new(&abi_entries(local), &abi_entries(declared)),
+        external: SetDiff::of_keys(
+            &selectors(&local_entry_points.external),
+            &selectors(&declared_entry_points.external),
+        ),
+        l1_handler: SetDiff::of_keys(
+            &selectors(&local_entry_points.l1_handler),
+            &selectors(&declared_entry_points.l1_handler),
+        ),
+        constructor: SetDiff::of_keys(
+            &selectors(&local_entry_points.constructor),
+            &selectors(&declared_entry_points.constructor),
+        ),
+        contract_class_version: Pair {
+            local: local.contract_class_version.clone(),
+            declared: declared.contract_class_version.clone(),
+        },
+        sierra_version: Pair {
+            local: local.sierra_version().map(|v| v.to_string()),
+            declared: declared.sierra_version().map(|v| v.to_string()),
+        },
+        compiler_version: Pair {
+            local: local.compiler_version().map(|v| v.to_string()),
+            declared: declared.compiler_version().map(|v| v.to_string()),
+        },
+        program_length: Pair {
+            local: local.sierra_program.len(),
+            declared: declared.sierra_program.len(),
+        },
+        // Functions are renumbered when the program changes, which doesn't
+        // change the interface
+        program_matches: program_body(local) == program_body(declared)
+            && entry_point_functions(local) == entry_point_functions(declared),
+    }
+}
+
+/// Program without its version header, felts normalized
+fn program_body(class: &ContractClass) -> Vec<String> {
+    class
+        .sierra_program
+        .iter()
+        .skip(PROGRAM_HEADER_LENGTH)
+        .map(|felt| normalize_felt(felt))
+        .collect()
+}
+
+fn normalize_felt(felt: &str) -> String {
+    let digits = felt.trim_start_matches("0x").trim_start_matches('0');
+    if digits.is_empty() {
+        return "0x0".to_string();
+    }
+    format!("0x{}", digits.to_lowercase())
+}
+
+/// Selectors of `entry_points`
+fn selectors(entry_points: &[SierraEntryPoint]) -> BTreeSet<String> {
+    entry_points
+        .iter()
+        .map(|entry_point| normalize_felt(&entry_point.selector))
+        .collect()
+}
+
+/// Function index of every entry point, by kind and selector
+fn entry_point_functions(class: &ContractClass) -> BTreeMap<(&'static str, String), u64> {
+    let entry_points = &class.entry_points_by_type;
+    [
+        ("external", &entry_points.external),
+        ("l1_handler", &entry_points.l1_handler),
+        ("constructor", &entry_points.constructor),
+    ]
+    .into_iter()
+    .flat_map(|(kind, entry_points)| {
+        entry_points.iter().map(move |entry_point| {
+            (
+                (kind, normalize_felt(&entry_point.selector)),
+                entry_point.function_idx,
+            )
+        })
+    })
+    .collect()
+}
+
+/// ABI items labelled `<type> <name>`, mapped to their JSON
+fn abi_entries(class: &ContractClass) -> BTreeMap<String, String> {
+    let items = match &class.abi {
+        Value::String(flattened) => serde_json::from_str(flattened).unwrap_or_default(),
+        Value::Array(items) => items.clone(),
+        _ => vec![],
+    };
+    items
+        .iter()
+        .map(|item| {
+            let field = |key: &str| item.get(key).and_then(Value::as_str).unwrap_or("?");
+            (
+                format!("{} {}", field("type"), field("name")),
+                item.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use camino::Utf8Path;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/hello_starknet.contract_class.json"
+    );
+
+    fn fixture() -> ContractClass {
+        ContractClass::from_file(Utf8Path::new(FIXTURE)).unwrap()
+    }
+
+    #[test]
+    fn test_identical_classes() {
+        let local = fixture();
+        let mut declared = fixture();
+        // Declared classes carry the ABI flattened to a string
+        declared.abi = Value::String(declared.abi_string().unwrap());
+
+        let diff = compare(&local, &declared);
+        assert!(diff.interface_matches());
+        assert!(diff.program_matches);
+        assert!(diff.verdict().starts_with("The local build matches"));
+    }
+
+    #[test]
+    fn test_program_differs() {
+        let local = fixture();
+        let mut declared = fixture();
+        let last = declared.sierra_program.len() - 1;
+        declared.sierra_program[last] = "0x42".to_string();
+
+        let diff = compare(&local, &declared);
+        assert!(diff.interface_matches());
+        assert!(!diff.program_matches);
+        assert_eq!(
+            diff.verdict(),
+            "ABI matches, Sierra program differs → compiler version or `sierra-replace-ids` mismatch"
+        );
+    }
+
+    #[test]
+    fn test_function_indices_differ() {
+        let local = fixture();
+        let mut declared = fixture();
+        for entry_point in &mut declared.entry_points_by_type.external {
+            entry_point.function_idx += 1;
+        }
+
+        let diff = compare(&local, &declared);
+        assert!(diff.interface_matches());
+        assert!(!diff.program_matches);
+        assert_eq!(
+            diff.verdict(),
+            "ABI matches, Sierra program differs → compiler version or `sierra-replace-ids` mismatch"
+        );
+    }
+
+    #[test]
+    fn test_compiler_version_differs() {
+        let local = fixture();
+        let mut declared = fixture();
+        declared.sierra_program[4] = "0xa".to_string();
+
+        let diff = compare(&local, &declared);
+        assert_eq!(diff.compiler_version.declared.as_deref(), Some("2.10.4"));
+        assert!(diff
+            .verdict()
+            .contains("Cairo 2.11.4 locally but 2.10.4 on chain"));
+    }
+
+    #[test]
+    fn test_interface_differs() {
+        let local = fixture();
+        let mut declared = fixture();
+        declared.entry_points_by_type.external.pop();
+        if let Value::Array(items) = &mut declared.abi {
+            items.retain(|item| item["type"] != "constructor");
+        }
+
+        let diff = compare(&local, &declared);
+        assert_eq!(diff.abi.local_only, vec!["constructor constructor"]);
+        assert_eq!(diff.external.local_only.len(), 1);
+        assert!(diff.verdict().starts_with("The interface differs"));
+    }
+}
@@ -1,5 +1,5 @@
 use reqwest::StatusCode;
-use scarb_metadata::{Metadata, PackageId};
+use scarb_metadata::Metadata;
 use std::fmt::{self, Formatter};
 use thiserror::Error;
 use url::Url;
@@ -68,8 +68,8 @@ fn edit_distance(s1: &str, s2: &str) -> usize {
     for (i, row) in matrix.iter_mut().enumerate().take(len1 + 1) {
         row[0] = i;
     }
-    for j in 0..=len2 {
-        matrix[0][j] = j;
+    for (j, cell) in matrix[0].iter_mut().enumerate() {
+        *cell = j;
     }
 
     for (i, c1) in s1.chars().enumerate() {
@@ -90,16 +90,23 @@ fn edit_distance(s1: &str, s2: &str) -> usize {
 
 #[derive(Debug, Error)]
 pub struct MissingPackage {
-    pub package_id: PackageId,
-    pub available: Vec<PackageId>,
+    /// Package name given with --package
+    pub name: String,
+    /// Names of the workspace members
+    pub available: Vec<String>,
 }
 
 impl MissingPackage {
     #[must_use]
-    pub fn new(package_id: &PackageId, metadata: &Metadata) -> Self {
+    pub fn new(name: &str, metadata: &Metadata) -> Self {
         Self {
-            package_id: package_id.clone(),
-            available: metadata.workspace.members.clone(),
+            name: name.to_string(),
+            available: metadata
+                .packages
+                .iter()
+                .filter(|package| metadata.workspace.members.contains(&package.id))
+                .map(|package| package.name.clone())
+                .collect(),
         }
     }
 
@@ -114,7 +121,7 @@ impl fmt::Display for MissingPackage {
             formatter,
             "[{}] Package '{}' not found in workspace.",
             self.error_code().as_str(),
-            self.package_id
+            self.name
         )?;
 
         if self.available.is_empty() {
@@ -133,17 +140,17 @@ impl fmt::Display for MissingPackage {
             }
 
             // Find closest match for suggestion
-            let package_names: Vec<String> = self.available.iter().map(|p| p.to_string()).collect();
-            if let Some(suggestion) =
-                find_closest_match(&self.package_id.to_string(), &package_names)
-            {
+            if let Some(suggestion) = find_closest_match(&self.name, &self.available) {
                 writeln!(formatter, "\nDid you mean '{suggestion}'?")?;
             }
 
             writeln!(formatter, "\nSuggestions:")?;
             writeln!(formatter, "  • Use --package <name> to specify a package")?;
             writeln!(formatter, "  • Check spelling of the package name")?;
-            writeln!(formatter, "  • Run 'scarb metadata' to list all packages")?;
+            writeln!(
+                formatter,
+                "  • Run 'voyager list' to see the contracts of each package"
+            )?;
         }
 
         Ok(())
@@ -252,16 +259,40 @@ impl fmt::Display for RequestFailure {
     }
 }
 
+/// Where a contract was looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractLookup {
+    /// Contracts listed in the `[tool.voyager]` section of the manifest
+    Manifest,
+    /// Contract modules found in the package sources
+    Sources,
+}
+
 #[derive(Debug, Error)]
 pub struct MissingContract {
     pub name: String,
     pub available: Vec<String>,
+    pub lookup: ContractLookup,
 }
 
 impl MissingContract {
     #[must_use]
     pub const fn new(name: String, available: Vec<String>) -> Self {
-        Self { name, available }
+        Self {
+            name,
+            available,
+            lookup: ContractLookup::Manifest,
+        }
+    }
+
+    /// No contract module named `name` was found in the package sources
+    #[must_use]
+    pub const fn in_sources(name: String, available: Vec<String>) -> Self {
+        Self {
+            name,
+            available,
+            lookup: ContractLookup::Sources,
+        }
     }
 
     pub const fn error_code(&self) -> ErrorCode {
@@ -271,6 +302,10 @@ impl MissingContract {
 
 impl fmt::Display for MissingContract {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if self.lookup == ContractLookup::Sources {
+            return self.fmt_sources(formatter);
+        }
+
         writeln!(
             formatter,
             "[{}] Contract '{}' not found in manifest file.",
@@ -319,3 +354,50 @@ impl fmt::Display for MissingContract {
         Ok(())
     }
 }
+
+impl MissingContract {
+    fn fmt_sources(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "[{}] Contract '{}' not found in the package sources.",
+            self.error_code().as_str(),
+            self.name
+        )?;
+
+        if self.available.is_empty() {
+            writeln!(
+                formatter,
+                "\nNo #[starknet::contract] or #[dojo::contract] modules were found."
+            )?;
+        } else {
+            writeln!(formatter, "\nContracts found in the sources:")?;
+            for contract in &self.available {
+                writeln!(formatter, "  • {contract}")?;
+            }
+
+            if let Some(suggestion) = find_closest_match(&self.name, &self.available) {
+                writeln!(formatter, "\nDid you mean '{suggestion}'?")?;
+            }
+        }
+
+        writeln!(formatter, "\nSuggestions:")?;
+        writeln!(
+            formatter,
+            "  • Use the name of the contract module, e.g. 'MyContract' for mod MyContract"
+        )?;
+        writeln!(
+            formatter,
+            "  • Check that the module has a #[starknet::contract] or #[dojo::contract] attribute"
+        )?;
+        writeln!(
+            formatter,
+            "  • Use --dry-run --explain to check that the file defining it is collected"
+        )?;
+        writeln!(
+            formatter,
+            "  • Use --package <name> if the contract is defined in another workspace member"
+        )?;
+
+        Ok(())
+    }
+}
@@ -30,7 +30,7 @@
 //! let class_hash = ClassHash::new("0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18")?;
 //!
 //! // Check if the class exists
-//! let exists = client.get_class(&class_hash)?;
+//! let exists = client.get_class(&class_hash)?.is_some();
 //! println!("Class exists: {}", exists);
 //! # Ok(())
 //! # }
@@ -56,3 +56,33 @@ pub mod voyager;
 
 /// Project type definitions and detection logic
 pub mod project;
+
+/// Local build of the verification payload before submission
+pub mod precheck;
+
+/// Export of the verification payload for review
+pub mod archive;
+
+/// Default argument values from configuration files and profiles
+pub mod config;
+
+/// Built-in and configured networks selectable with --network
+pub mod network;
+
+/// Interactive prompts with non-interactive and CI fallbacks
+pub mod prompt;
+
+/// Discovery of contract modules in Cairo sources
+pub mod contracts;
+
+/// Size budget of the verification payload
+pub mod payload;
+
+/// Starknet JSON-RPC client for on-chain class lookups
+pub mod rpc;
+
+/// Comparison of a local build with the declared class
+pub mod diagnose;
+
+/// Toolchain versions pinned by a project or recorded in a declared class
+pub mod toolchain;
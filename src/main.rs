@@ -1,29 +1,178 @@
 mod args;
-use crate::args::{Args, Commands, VerifyArgs};
+mod output;
+use crate::args::{Args, Commands, ListArgs, OutputFormat, PollArgs, StatusArgs, VerifyArgs};
+use crate::output::{
+    BatchReport, ContractReport, ErrorReport, FileReport, ListReport, PackageReport, StatusReport,
+    VerifyReport,
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use chrono::{DateTime, Utc};
-use clap::Parser;
+use clap::FromArgMatches;
 use colored::*;
-use dialoguer::Select;
 use itertools::Itertools;
 use log::{debug, info, warn};
 use scarb_metadata::PackageMetadata;
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::process::ExitCode;
+use std::sync::OnceLock;
 use std::time::{Duration, UNIX_EPOCH};
+use std::{env, fs, mem};
+use tempfile::NamedTempFile;
 use thiserror::Error;
 use verifier::{
     api::{
-        poll_verification_status, ApiClient, ApiClientError, FileInfo, ProjectMetadataInfo,
-        VerificationJob, VerifyJobStatus,
+        ApiClient, ApiClientError, CancellationToken, ClassInfo, FileInfo, Poller,
+        ProjectMetadataInfo, StatusUpdate, VerificationError, VerificationJob, VerifyJobStatus,
     },
-    class_hash::ClassHash,
+    archive,
+    class_hash::{self, ArtifactError, ClassHash, ContractClass},
+    contracts,
+    diagnose::{self, ClassDiff},
     errors, license,
+    network::{NetworkEntry, NetworkRegistry},
+    payload::{self, PayloadError, PayloadFile},
+    precheck::{self, PrecheckError},
     project::ProjectType,
-    resolver, voyager,
+    prompt::{Choice, Fallback, PromptError},
+    resolver::{self, FileDecision, Reason},
+    rpc::{self, DeclaredClass, RpcClient, RpcError},
+    toolchain::{self, Overrides, Pins, Toolchain, ToolchainError},
+    voyager,
 };
 
+/// A single contract selected for verification
+#[derive(Debug, Clone)]
+struct VerificationTarget {
+    contract_name: String,
+    class_hash: ClassHash,
+    /// Deployed instance of the class, when verifying by address
+    address: Option<String>,
+    package: Option<String>,
+    /// Contract file relative to the package root, as listed in [tool.voyager]
+    contract_path: Option<Utf8PathBuf>,
+}
+
+impl VerificationTarget {
+    /// Target given on the command line, resolving --address into the hash
+    /// of the deployed class.
+    fn from_args(
+        args: &VerifyArgs,
+        api_client: &ApiClient,
+        networks: &NetworkRegistry,
+    ) -> Result<Self, CliError> {
+        let Some(contract_name) = &args.contract_name else {
+            return Err(CliError::NoTarget);
+        };
+        let class_hash = match (&args.class_hash, &args.address) {
+            (Some(class_hash), None) => class_hash.clone(),
+            (class_hash, Some(address)) => {
+                let deployed = resolve_address(api_client, args, networks, address)?;
+                match class_hash {
+                    Some(class_hash) if !class_hash.matches(&deployed) => {
+                        return Err(CliError::AddressClassMismatch {
+                            address: address.clone(),
+                            class_hash: class_hash.clone(),
+                            deployed,
+                        });
+                    }
+                    _ => {
+                        info!("Contract {address} is an instance of class {deployed}");
+                        deployed
+                    }
+                }
+            }
+            (None, None) => return Err(CliError::NoTarget),
+        };
+        Ok(Self {
+            contract_name: contract_name.clone(),
+            class_hash,
+            address: args.address.clone(),
+            package: args.package.clone(),
+            contract_path: None,
+        })
+    }
+}
+
+/// Result of preparing, and unless it's a dry run, submitting a contract
+struct Submission {
+    /// `None` for dry runs and skipped classes
+    job_id: Option<String>,
+    /// Payload file names, relative to the submitted project root
+    files: Vec<String>,
+    /// Class as found on the network before submitting, `None` for dry runs
+    class_info: Option<ClassInfo>,
+    /// Class as declared on chain, `None` without an RPC endpoint
+    declared_class: Option<DeclaredClass>,
+    /// Every candidate file with its decision, only with --explain
+    explanation: Option<Vec<FileReport>>,
+    /// Package defining the contract, `None` if skipped before selecting it
+    package: Option<String>,
+    /// What was submitted, `None` for dry runs and skipped classes
+    context: Option<VerificationContext>,
+}
+
+impl Submission {
+    /// Submission was skipped because the class is already verified
+    fn already_verified(&self) -> bool {
+        self.job_id.is_none() && self.class_info.as_ref().is_some_and(ClassInfo::is_verified)
+    }
+}
+
+/// Outcome of a single contract submission in batch mode
+struct BatchEntry {
+    package: String,
+    contract_name: String,
+    class_hash: Option<ClassHash>,
+    address: Option<String>,
+    outcome: Result<Submission, CliError>,
+    /// Last observed job state with --watch
+    job: Option<VerificationJob>,
+    /// Polling error with --watch
+    watch_error: Option<CliError>,
+}
+
+impl BatchEntry {
+    fn into_report(self, license_info: &license::LicenseInfo, dry_run: bool) -> VerifyReport {
+        let (already_verified, job_id, files, class, error) = match self.outcome {
+            Ok(submission) => (
+                submission.already_verified(),
+                submission.job_id,
+                submission.files,
+                submission.class_info,
+                self.watch_error
+                    .map(|e| ErrorReport::new(e.error_code(), &e)),
+            ),
+            Err(e) => (
+                false,
+                None,
+                vec![],
+                None,
+                Some(ErrorReport::new(e.error_code(), &e)),
+            ),
+        };
+        VerifyReport {
+            contract_name: self.contract_name,
+            class_hash: self
+                .class_hash
+                .map_or_else(String::new, |hash| hash.to_string()),
+            address: self.address,
+            package: Some(self.package),
+            license: license_info.display_string().to_string(),
+            dry_run,
+            already_verified,
+            job_id,
+            files,
+            explain: None,
+            class,
+            job: self.job,
+            diagnosis: None,
+            error,
+        }
+    }
+}
+
 #[derive(Debug)]
 struct VerificationContext {
     project_type: ProjectType,
@@ -31,6 +180,8 @@ struct VerificationContext {
     contract_file: String,
     package_meta: PackageMetadata,
     file_infos: Vec<FileInfo>,
+    /// Narrowed workspace manifest, submitted in place of the original one
+    _workspace_manifest: Option<NamedTempFile>,
 }
 
 #[derive(Debug, Error)]
@@ -44,8 +195,13 @@ pub enum CliError {
     #[error(transparent)]
     MissingPackage(#[from] errors::MissingPackage),
 
-    #[error("[E015] Class hash '{0}' is not declared\n\nSuggestions:\n  • Verify the class hash is correct\n  • Check that the contract has been declared on the network\n  • Ensure you're using the correct network (mainnet/testnet)\n  • Use a block explorer to verify the class hash exists")]
-    NotDeclared(ClassHash),
+    #[error("[E015] Class hash '{class_hash}' is not declared on {network}\n{}\nSuggestions:\n  • Verify the class hash is correct\n  • Check that the contract has been declared on the network\n  • Ensure you're using the correct network (mainnet/testnet)\n  • Use a block explorer to verify the class hash exists", declared_on_hint(declared_on))]
+    NotDeclared {
+        class_hash: ClassHash,
+        network: String,
+        /// Other known networks where the class is declared
+        declared_on: Vec<String>,
+    },
 
     #[error("[E016] No contracts selected for verification\n\nSuggestions:\n  • Use --contract-name <name> to specify a contract\n  • Check that contracts are defined in [tool.voyager] section\n  • Verify your Scarb.toml contains contract definitions\n  • Use 'scarb metadata' to list available contracts")]
     NoTarget,
@@ -71,6 +227,12 @@ pub enum CliError {
     #[error(transparent)]
     Voyager(#[from] voyager::Error),
 
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+
+    #[error(transparent)]
+    Precheck(#[from] PrecheckError),
+
     #[error("[E019] File '{path}' exceeds maximum size limit of {max_size} bytes (actual: {actual_size} bytes)\n\nSuggestions:\n  • Reduce the file size by removing unnecessary content\n  • Split large files into smaller modules\n  • Check if the file contains generated or temporary content\n  • Use .gitignore to exclude large files that shouldn't be verified")]
     FileSizeLimit {
         path: Utf8PathBuf,
@@ -78,7 +240,7 @@ pub enum CliError {
         actual_size: usize,
     },
 
-    #[error("[E024] File '{path}' has invalid file type (extension: {extension})\n\nSuggestions:\n  • Only include Cairo source files (.cairo)\n  • Include project configuration files (.toml, .lock)\n  • Include documentation files (.md, .txt)\n  • Remove binary or executable files from the project\n  • Allowed extensions: .cairo, .toml, .lock, .md, .txt, .json")]
+    #[error("[E024] File '{path}' has invalid file type (extension: {extension})\n\nSuggestions:\n  • Only include Cairo source files (.cairo)\n  • Include project configuration files (.toml, .lock)\n  • Include documentation files (.md, .txt)\n  • Remove binary or executable files from the project\n  • Allowed extensions: .cairo, .toml, .lock, .md, .txt, .json\n  • Add an --include glob or [tool.voyager] include entry to submit it anyway")]
     InvalidFileType {
         path: Utf8PathBuf,
         extension: String,
@@ -94,8 +256,42 @@ pub enum CliError {
     #[error("[E026] Dojo project validation failed\n\nSuggestions:\n  • Ensure dojo-core is listed in dependencies\n  • Check that Scarb.toml is properly configured for Dojo\n  • Verify project structure follows Dojo conventions\n  • Run 'sozo build' to test project compilation")]
     DojoValidationFailed,
 
-    #[error("[E027] Interactive prompt failed\n\nSuggestions:\n  • Use --project-type=scarb or --project-type=dojo to skip prompt\n  • Ensure terminal supports interactive input\n  • Check that stdin is available")]
-    InteractivePromptFailed(#[from] dialoguer::Error),
+    #[error(transparent)]
+    Prompt(#[from] PromptError),
+
+    #[error("[E028] No class hash available for contract '{contract}'\n\nSuggestions:\n  • Add class-hash = \"0x...\" or the address of a deployed instance to the contract entry in [tool.voyager]\n  • Run 'scarb build' so the hash can be computed from target/<profile>/*.contract_class.json\n  • Verify the contract separately with --class-hash")]
+    MissingClassHash { contract: String },
+
+    #[error("[E029] Batch verification failed for {failed} of {total} contracts\n\nSuggestions:\n  • Check the errors reported above for each failed contract\n  • Fix the failing entries in [tool.voyager] and re-run\n  • Verify failing contracts individually with --contract-name")]
+    BatchFailed { failed: usize, total: usize },
+
+    #[error("[E040] Failed to serialize JSON output: {0}\n\nThis is an internal error. Please report this issue.")]
+    Output(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Archive(#[from] archive::ArchiveError),
+
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+
+    #[error("[E057] No contract is deployed at {address} on {network}\n\nSuggestions:\n  • Check that the address is correct\n  • Ensure you're using the correct network (mainnet/sepolia)\n  • Use --class-hash to verify a class that was declared but not deployed")]
+    AddressNotFound { address: String, network: String },
+
+    #[error("[E058] Contract {address} is an instance of class {deployed}, not {class_hash}\n\nSuggestions:\n  • Drop --class-hash to verify the class the contract currently has\n  • Drop --address to verify {class_hash}, e.g. a previous implementation of an upgraded contract")]
+    AddressClassMismatch {
+        address: String,
+        class_hash: ClassHash,
+        deployed: ClassHash,
+    },
+
+    #[error("[E059] --diagnose needs a Starknet RPC endpoint to fetch the declared class\n\nSuggestions:\n  • Pass --rpc-url <URL>\n  • Set rpc-url in the [networks.<name>] section of your configuration")]
+    DiagnoseWithoutRpc,
+
+    #[error(transparent)]
+    Toolchain(#[from] ToolchainError),
 }
 
 impl CliError {
@@ -104,34 +300,224 @@ impl CliError {
             Self::Args(_) => "E020",
             Self::Api(e) => e.error_code(),
             Self::MissingPackage(e) => e.error_code().as_str(),
-            Self::NotDeclared(_) => "E015",
+            Self::NotDeclared { .. } => "E015",
             Self::NoTarget => "E016",
             Self::MultipleContracts => "E017",
             Self::MissingContract(e) => e.error_code().as_str(),
             Self::Resolver(e) => e.error_code(),
             Self::StripPrefix { .. } => "E018",
             Self::Utf8(_) => "E023",
-            Self::Voyager(_) => "E999",
+            Self::Voyager(e) => e.error_code(),
+            Self::Artifact(e) => e.error_code(),
+            Self::Precheck(e) => e.error_code(),
             Self::FileSizeLimit { .. } => "E019",
             Self::InvalidFileType { .. } => "E024",
             Self::InvalidProjectType { .. } => "E025",
             Self::DojoValidationFailed => "E026",
-            Self::InteractivePromptFailed(_) => "E027",
+            Self::Prompt(e) => e.error_code(),
+            Self::MissingClassHash { .. } => "E028",
+            Self::BatchFailed { .. } => "E029",
+            Self::Output(_) => "E040",
+            Self::Archive(e) => e.error_code(),
+            Self::Payload(e) => e.error_code(),
+            Self::Rpc(e) => e.error_code(),
+            Self::AddressNotFound { .. } => "E057",
+            Self::AddressClassMismatch { .. } => "E058",
+            Self::DiagnoseWithoutRpc => "E059",
+            Self::Toolchain(e) => e.error_code(),
+        }
+    }
+}
+
+/// Process exit status, grouped by error class so that scripts can tell
+/// problems with the project apart from service outages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitStatus {
+    /// Any error not covered by a more specific class
+    Failure = 1,
+    /// Invalid arguments or input that doesn't match the network
+    Usage = 2,
+    /// The project, its manifest or its contracts couldn't be resolved
+    Project = 3,
+    /// The verification service couldn't be reached or failed
+    Network = 4,
+    /// The submitted sources failed to compile
+    CompilationFailure = 5,
+    /// The sources compiled to a different class hash
+    VerificationFailure = 6,
+    /// The job didn't finish while we were waiting for it
+    InProgress = 7,
+    /// Waiting for the job was interrupted with Ctrl-C
+    Interrupted = 130,
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        Self::from(status as u8)
+    }
+}
+
+impl CliError {
+    fn exit_status(&self) -> ExitStatus {
+        match self {
+            Self::Api(e) => match e {
+                ApiClientError::CannotBeBase(_)
+                | ApiClientError::UrlCannotBeBase(_)
+                | ApiClientError::JobNotFound(_) => ExitStatus::Usage,
+                ApiClientError::Reqwest(_) => ExitStatus::Network,
+                // Client errors such as an oversized payload won't go away on retry
+                ApiClientError::Failure(failure)
+                    if failure.status.is_server_error()
+                        || failure.status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || failure.status == reqwest::StatusCode::REQUEST_TIMEOUT =>
+                {
+                    ExitStatus::Network
+                }
+                ApiClientError::Failure(_) | ApiClientError::IoError(_) => ExitStatus::Failure,
+                ApiClientError::InProgress | ApiClientError::Timeout { .. } => {
+                    ExitStatus::InProgress
+                }
+                ApiClientError::Cancelled(_) => ExitStatus::Interrupted,
+                ApiClientError::Verify(VerificationError::CompilationFailure(_)) => {
+                    ExitStatus::CompilationFailure
+                }
+                ApiClientError::Verify(VerificationError::VerificationFailure(_)) => {
+                    ExitStatus::VerificationFailure
+                }
+            },
+            Self::NotDeclared { .. }
+            | Self::Prompt(_)
+            | Self::AddressNotFound { .. }
+            | Self::AddressClassMismatch { .. }
+            | Self::DiagnoseWithoutRpc
+            | Self::Toolchain(ToolchainError::LocalBuildMismatch { .. }) => ExitStatus::Usage,
+            Self::Args(_)
+            | Self::MissingPackage(_)
+            | Self::NoTarget
+            | Self::MultipleContracts
+            | Self::MissingContract(_)
+            | Self::Resolver(_)
+            | Self::StripPrefix { .. }
+            | Self::Utf8(_)
+            | Self::Voyager(_)
+            | Self::Artifact(_)
+            | Self::FileSizeLimit { .. }
+            | Self::InvalidFileType { .. }
+            | Self::InvalidProjectType { .. }
+            | Self::DojoValidationFailed
+            | Self::MissingClassHash { .. }
+            | Self::Payload(_)
+            | Self::Toolchain(_) => ExitStatus::Project,
+            Self::Precheck(e) => match e {
+                PrecheckError::BuildFailed { .. } => ExitStatus::CompilationFailure,
+                PrecheckError::HashMismatch { .. } => ExitStatus::VerificationFailure,
+                PrecheckError::ArtifactNotFound { .. } | PrecheckError::Artifact(_) => {
+                    ExitStatus::Project
+                }
+                PrecheckError::Stage { .. } | PrecheckError::ToolUnavailable { .. } => {
+                    ExitStatus::Failure
+                }
+            },
+            Self::Rpc(RpcError::Request { .. } | RpcError::Rpc { .. }) => ExitStatus::Network,
+            Self::Rpc(RpcError::Decode { .. }) => ExitStatus::Failure,
+            Self::Archive(archive::ArchiveError::UnsupportedFormat(_)) => ExitStatus::Usage,
+            Self::Archive(archive::ArchiveError::ReservedName) => ExitStatus::Project,
+            Self::BatchFailed { .. } | Self::Output(_) | Self::Archive(_) => ExitStatus::Failure,
+        }
+    }
+}
+
+fn declared_on_hint(networks: &[String]) -> String {
+    match networks {
+        [] => String::new(),
+        networks => {
+            let names = networks.join(", ");
+            format!(
+                "\nThe class is declared on: {names}\nDid you mean '--network {}'?\n",
+                networks[0]
+            )
         }
     }
 }
 
+fn display_already_verified(class_hash: &ClassHash, info: &ClassInfo) {
+    println!();
+    println!("✅ Class {class_hash} is already verified, nothing to submit.");
+    if let Some(name) = info.name() {
+        println!("Contract name: {name}");
+    }
+    if let Some(license) = info.license() {
+        println!("License: {license}");
+    }
+    println!("\nUse --force to submit it again.");
+    println!();
+}
+
 fn display_verification_job_id(job_id: &str) {
     println!();
     println!("verification job id: {}", job_id.green().bold());
     println!();
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> ExitCode {
     env_logger::init();
-    let Args { command: cmd } = Args::parse();
+    let argv: Vec<OsString> = env::args_os().collect();
+    let (command, networks) = match args::command_with_config(&argv) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitStatus::Usage.into();
+        }
+    };
+    let Args { command: cmd, .. } = match command
+        .try_get_matches_from(&argv)
+        .and_then(|matches| Args::from_arg_matches(&matches))
+    {
+        Ok(args) => args,
+        Err(e) => return parse_error_exit(&e),
+    };
 
-    match &cmd {
+    match run(&cmd, &networks) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            e.exit_status().into()
+        }
+    }
+}
+
+/// Report a command line parsing error. Failing to load the project given by
+/// `--path` is a project resolution error rather than a usage error.
+fn parse_error_exit(error: &clap::Error) -> ExitCode {
+    let project_error = std::error::Error::source(error)
+        .and_then(|source| source.downcast_ref::<args::ProjectError>());
+    if let Some(project_error) = project_error {
+        eprintln!("Error: {project_error}");
+        return ExitStatus::Project.into();
+    }
+
+    // Prints help and version to stdout, anything else to stderr
+    let _ = error.print();
+    if error.use_stderr() {
+        ExitStatus::Usage.into()
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Network entry for the selected `--network`, or the one serving `url`
+/// when a custom URL is given.
+fn selected_network<'a>(
+    networks: &'a NetworkRegistry,
+    name: Option<&str>,
+    url: &url::Url,
+) -> Option<&'a NetworkEntry> {
+    name.and_then(|name| networks.get(name))
+        .or_else(|| networks.find_by_api_url(url))
+}
+
+fn run(cmd: &Commands, networks: &NetworkRegistry) -> Result<(), CliError> {
+    match cmd {
         Commands::Verify(args) => {
             let api_client = ApiClient::new(args.network_url.url.clone())?;
 
@@ -143,183 +529,1285 @@ fn main() -> anyhow::Result<()> {
 
             license::warn_if_no_license(&license_info);
 
-            let job_id = submit(&api_client, args, &license_info).map_err(|e| {
-                if let CliError::Api(ApiClientError::Verify(ref verification_error)) = e {
-                    eprintln!("\nSuggestions:");
-                    for suggestion in verification_error.suggestions() {
-                        eprintln!("  • {suggestion}");
-                    }
-                } else if let CliError::Api(ApiClientError::Failure(ref _request_failure)) = e {
-                    // RequestFailure errors already include suggestions in their display
-                }
-                e
-            })?;
-            if job_id != "dry-run" {
-                display_verification_job_id(&job_id);
-
-                // If --watch flag is enabled, poll for verification result
-                if args.watch {
-                    let status = check(&api_client, &job_id).map_err(|e| {
-                        if let CliError::Api(ApiClientError::Verify(ref verification_error)) = e {
-                            eprintln!("\nSuggestions:");
-                            for suggestion in verification_error.suggestions() {
-                                eprintln!("  • {suggestion}");
-                            }
-                        } else if let CliError::Api(ApiClientError::Failure(ref _request_failure)) =
-                            e
-                        {
-                            // RequestFailure errors already include suggestions in their display
-                        }
-                        e
-                    })?;
-                    info!("{status:?}");
-                }
+            if args.all {
+                submit_all(&api_client, args, &license_info, networks)?;
+                return Ok(());
             }
+
+            verify(&api_client, args, &license_info, networks)?;
         }
         Commands::Status(args) => {
             let api_client = ApiClient::new(args.network_url.url.clone())?;
-            let status = check(&api_client, &args.job).map_err(|e| {
-                if let CliError::Api(ApiClientError::Verify(ref verification_error)) = e {
-                    eprintln!("\nSuggestions:");
-                    for suggestion in verification_error.suggestions() {
-                        eprintln!("  • {suggestion}");
-                    }
-                } else if let CliError::Api(ApiClientError::Failure(ref _request_failure)) = e {
-                    // RequestFailure errors already include suggestions in their display
+            let network =
+                selected_network(networks, args.network.as_deref(), &args.network_url.url);
+            status(&api_client, args, network)?;
+        }
+        Commands::List(args) => list(args)?,
+        Commands::Hash(args) => {
+            let class_hash =
+                class_hash::compute_class_hash(&args.artifact).map_err(CliError::from)?;
+            println!("{class_hash}");
+        }
+    }
+    Ok(())
+}
+
+fn print_suggestions(error: &CliError) {
+    // Other errors already include suggestions in their display
+    if let CliError::Api(ApiClientError::Verify(verification_error)) = error {
+        eprintln!("\nSuggestions:");
+        for suggestion in verification_error.suggestions() {
+            eprintln!("  • {suggestion}");
+        }
+    }
+}
+
+/// Verify a single contract and report the outcome in the requested format.
+fn verify(
+    api_client: &ApiClient,
+    args: &VerifyArgs,
+    license_info: &license::LicenseInfo,
+    networks: &NetworkRegistry,
+) -> Result<(), CliError> {
+    let mut report = VerifyReport {
+        contract_name: args.contract_name.clone().unwrap_or_default(),
+        // Known once --address is resolved
+        class_hash: args
+            .class_hash
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        address: args.address.clone(),
+        package: args.package.clone(),
+        license: license_info.display_string().to_string(),
+        dry_run: args.dry_run,
+        already_verified: false,
+        job_id: None,
+        files: vec![],
+        explain: None,
+        class: None,
+        job: None,
+        diagnosis: None,
+        error: None,
+    };
+
+    let result = verify_into(api_client, args, license_info, networks, &mut report);
+    if let Err(e) = &result {
+        print_suggestions(e);
+    }
+
+    if args.format == OutputFormat::Json {
+        report.error = result
+            .as_ref()
+            .err()
+            .map(|e| ErrorReport::new(e.error_code(), e));
+        output::print_json(&report)?;
+    }
+
+    result
+}
+
+fn verify_into(
+    api_client: &ApiClient,
+    args: &VerifyArgs,
+    license_info: &license::LicenseInfo,
+    networks: &NetworkRegistry,
+    report: &mut VerifyReport,
+) -> Result<(), CliError> {
+    check_local_builds(args)?;
+    let target = &VerificationTarget::from_args(args, api_client, networks)?;
+    report.class_hash = target.class_hash.to_string();
+
+    // Fail before submitting rather than once the job has failed
+    if args.diagnose {
+        let network = selected_network(networks, args.network.as_deref(), &args.network_url.url);
+        if rpc_client(args, network).is_none() {
+            return Err(CliError::DiagnoseWithoutRpc);
+        }
+        if !args.watch() {
+            warn!("--diagnose has no effect without --watch");
+        }
+    }
+
+    let project_type = determine_project_type(args)?;
+    let submission = submit(
+        api_client,
+        args,
+        target,
+        project_type,
+        license_info,
+        networks,
+    )?;
+    report.already_verified = submission.already_verified();
+    if report.already_verified && args.format == OutputFormat::Text {
+        if let Some(info) = &submission.class_info {
+            display_already_verified(&target.class_hash, info);
+        }
+    }
+    if submission.package.is_some() {
+        report.package = submission.package;
+    }
+    report.files = submission.files;
+    report.explain = submission.explanation;
+    report.class = submission.class_info;
+
+    if let Some(job_id) = submission.job_id {
+        report.job_id = Some(job_id.clone());
+        if args.format == OutputFormat::Text {
+            display_verification_job_id(&job_id);
+        }
+
+        // If --watch flag is enabled, poll for verification result
+        if args.watch() {
+            let network =
+                selected_network(networks, args.network.as_deref(), &args.network_url.url);
+            let status = check(
+                api_client,
+                &job_id,
+                &args.poll,
+                args.format,
+                network,
+                interrupt_token(),
+                &mut report.job,
+            );
+            if let (
+                Err(CliError::Api(ApiClientError::Verify(VerificationError::VerificationFailure(
+                    _,
+                )))),
+                Some(context),
+            ) = (&status, &submission.context)
+            {
+                if args.diagnose {
+                    let declared_class = submission
+                        .declared_class
+                        .as_ref()
+                        .and_then(DeclaredClass::sierra);
+                    report.diagnosis = diagnose_failure(args, target, context, declared_class);
                 }
-                e
-            })?;
+            }
+            let status = status?;
             info!("{status:?}");
         }
     }
+
+    Ok(())
+}
+
+/// Compare a local build of the payload with the declared class to tell
+/// why the verification failed. Failing to do so is only reported, as the
+/// verification failure is what matters.
+fn diagnose_failure(
+    args: &VerifyArgs,
+    target: &VerificationTarget,
+    context: &VerificationContext,
+    declared_class: Option<&ContractClass>,
+) -> Option<ClassDiff> {
+    let Some(declared_class) = declared_class else {
+        warn!(
+            "Class {} isn't a Sierra class on chain, skipping the comparison with the local build",
+            target.class_hash
+        );
+        return None;
+    };
+    let diff = compare_with_declared(target, context, declared_class)
+        .inspect_err(|e| warn!("Could not compare the local build with the declared class:\n{e}"))
+        .ok()?;
+    if args.format == OutputFormat::Text {
+        println!(
+            "\n🔬 Local build compared with the declared class {}:\n{diff}",
+            target.class_hash
+        );
+    }
+    Some(diff)
+}
+
+fn compare_with_declared(
+    target: &VerificationTarget,
+    context: &VerificationContext,
+    declared_class: &ContractClass,
+) -> Result<ClassDiff, CliError> {
+    info!("🔍 Building the payload locally to compare it with the declared class");
+    let build = precheck::build_payload(
+        &context.file_infos,
+        &context.project_dir_path,
+        &context.project_type,
+        &context.package_meta,
+        &target.contract_name,
+    )?;
+    let local = ContractClass::from_file(&build.artifact)?;
+    Ok(diagnose::compare(&local, declared_class))
+}
+
+/// Poll a job and report its final state in the requested format.
+fn status(
+    api_client: &ApiClient,
+    args: &StatusArgs,
+    network: Option<&NetworkEntry>,
+) -> Result<(), CliError> {
+    let mut job = None;
+    let result = check(
+        api_client,
+        &args.job,
+        &args.poll,
+        args.format,
+        network,
+        interrupt_token(),
+        &mut job,
+    );
+    if let Err(e) = &result {
+        print_suggestions(e);
+    }
+
+    if args.format == OutputFormat::Json {
+        output::print_json(&StatusReport {
+            job_id: args.job.clone(),
+            job,
+            error: result
+                .as_ref()
+                .err()
+                .map(|e| ErrorReport::new(e.error_code(), e)),
+        })?;
+    }
+
+    let status = result?;
+    info!("{status:?}");
+    Ok(())
+}
+
+fn submit(
+    api_client: &ApiClient,
+    args: &VerifyArgs,
+    target: &VerificationTarget,
+    project_type: ProjectType,
+    license_info: &license::LicenseInfo,
+    networks: &NetworkRegistry,
+) -> Result<Submission, CliError> {
+    info!("🚀 Starting verification for project at: {}", args.path);
+
+    // Log the selected build tool
+    match project_type {
+        ProjectType::Dojo => info!("Using sozo build for Dojo project"),
+        ProjectType::Scarb => info!("Using scarb build for Scarb project"),
+        ProjectType::Auto => unreachable!("Auto should be resolved by now"),
+    }
+
+    // Fail fast instead of waiting for the server to reject an unknown class
+    let (class_info, declared_class) = if args.dry_run {
+        (None, None)
+    } else {
+        let (info, class) = ensure_declared(api_client, args, networks, &target.class_hash)?;
+        (Some(info), class)
+    };
+
+    if let Some(info) = class_info.as_ref().filter(|info| info.is_verified()) {
+        if !args.force {
+            info!(
+                "Class {} is already verified, skipping submission",
+                target.class_hash
+            );
+            return Ok(Submission {
+                job_id: None,
+                files: vec![],
+                class_info,
+                declared_class,
+                explanation: None,
+                package: target.package.clone(),
+                context: None,
+            });
+        }
+        warn!(
+            "Class {} is already verified as '{}', submitting again due to --force",
+            target.class_hash,
+            info.name().unwrap_or("unknown")
+        );
+    }
+
+    let metadata = args.path.metadata();
+
+    // Determine test_files setting - default to true for Dojo projects
+    let include_test_files = match project_type {
+        ProjectType::Dojo if args.no_test_files => false,
+        ProjectType::Dojo => {
+            if !args.test_files() {
+                info!("🧪 Including test files by default for Dojo project");
+            }
+            true
+        }
+        _ => args.test_files(),
+    };
+
+    // Gather packages and sources
+    let (packages, package) =
+        gather_packages_and_validate(args, metadata, target, include_test_files)?;
+    if let Some(package_meta) = packages.iter().find(|p| p.name == package) {
+        // Fail before uploading sources the declared compiler can't reproduce
+        check_toolchain(
+            args,
+            metadata,
+            package_meta,
+            class_info.as_ref(),
+            declared_class.as_ref().and_then(DeclaredClass::sierra),
+            &target.class_hash,
+        )?;
+    }
+    // Only the selected package and what it needs to build are submitted
+    let packages = resolver::path_dependency_closure(&packages, &package);
+    let workspace_files = workspace_manifest_decisions(args, metadata, &packages)?;
+    let target = &VerificationTarget {
+        package: Some(package),
+        ..target.clone()
+    };
+    let (mut sources, glob_included) = collect_source_files(args, &packages, include_test_files)?;
+    sources.extend(
+        workspace_files
+            .iter()
+            .filter(|decision| decision.included)
+            .map(|decision| decision.path.clone()),
+    );
+
+    // The build runs from the workspace root only if its manifest is sent
+    let sends_workspace_manifest = sources.contains(&metadata.workspace.manifest_path);
+    let project_root = match packages.first() {
+        Some(package) if is_workspace(metadata) && !sends_workspace_manifest => {
+            package.root.clone()
+        }
+        _ => args.path.root_dir().clone(),
+    };
+
+    // Prepare project structure
+    let (mut file_infos, package_meta, contract_file, project_dir_path) =
+        prepare_project_for_verification(
+            args,
+            target,
+            metadata,
+            &packages,
+            sources,
+            &glob_included,
+            &project_root,
+        )?;
+
+    // Scarb loads every member listed by the workspace manifest, so the
+    // submitted one only lists the submitted packages
+    let workspace_manifest = if sends_workspace_manifest {
+        let narrowed = narrowed_workspace_manifest(metadata, &packages)?;
+        for file in &mut file_infos {
+            if file.path == metadata.workspace.manifest_path {
+                file.path = narrowed.path().to_path_buf();
+            }
+        }
+        Some(narrowed)
+    } else {
+        None
+    };
+
+    // Log verification info
+    log_verification_info(
+        target,
+        &local_toolchain(args),
+        &file_infos,
+        &contract_file,
+        license_info,
+    );
+
+    // Fail before uploading a payload the server would reject
+    check_payload_size(args, networks, &packages, &file_infos)?;
+
+    let files = file_infos
+        .iter()
+        .map(|file| file.name.clone())
+        .sorted()
+        .collect();
+
+    // Build the exact payload locally before anything is uploaded
+    if args.precheck {
+        let class_hash = precheck::run(
+            &file_infos,
+            &project_dir_path,
+            &project_type,
+            &package_meta,
+            &target.contract_name,
+            &target.class_hash,
+        )?;
+        info!("✅ Precheck passed: payload builds to class hash {class_hash}");
+    }
+
+    let context = VerificationContext {
+        project_type,
+        project_dir_path,
+        contract_file,
+        package_meta,
+        file_infos,
+        _workspace_manifest: workspace_manifest,
+    };
+
+    // Execute verification unless dry run is requested
+    if !args.dry_run {
+        let job_id = execute_verification(api_client, args, target, &context, license_info)?;
+        return Ok(Submission {
+            job_id: Some(job_id),
+            files,
+            class_info,
+            declared_class,
+            explanation: None,
+            package: target.package.clone(),
+            context: Some(context),
+        });
+    }
+
+    if let Some(archive_path) = &args.output_archive {
+        let project_meta = project_metadata_info(args, target, &context);
+        let payload_metadata = archive::PayloadMetadata::new(
+            &target.contract_name,
+            &project_meta,
+            Some(license_info.display_string()),
+        );
+        archive::write_payload(archive_path, &context.file_infos, &payload_metadata)?;
+        if args.format == OutputFormat::Text {
+            println!(
+                "📦 Wrote {} payload files and {} to {archive_path}",
+                context.file_infos.len(),
+                archive::METADATA_FILE
+            );
+        }
+    }
+
+    let explanation = if args.explain {
+        let decisions = explain_source_files(args, &packages, workspace_files, include_test_files)?;
+        if args.format == OutputFormat::Text {
+            display_explanation(&decisions);
+        }
+        Some(decisions)
+    } else {
+        None
+    };
+
+    info!("Dry run mode: collected files for verification but skipping submission due to --dry-run flag");
+    Ok(Submission {
+        job_id: None,
+        files,
+        class_info,
+        declared_class,
+        explanation,
+        package: target.package.clone(),
+        context: None,
+    })
+}
+
+/// Check that `class_hash` is declared on the selected network, looking it up
+/// on the other known networks when it isn't. When an RPC endpoint is
+/// available, the chain is asked too, as the explorer may lag behind it, and
+/// the class it returns is kept for the toolchain check and the diagnosis.
+fn ensure_declared(
+    api_client: &ApiClient,
+    args: &VerifyArgs,
+    networks: &NetworkRegistry,
+    class_hash: &ClassHash,
+) -> Result<(ClassInfo, Option<DeclaredClass>), CliError> {
+    let network = selected_network(networks, args.network.as_deref(), &args.network_url.url);
+    let declared_class = match rpc_client(args, network) {
+        Some(rpc) => match declared_on_chain(&rpc, network, class_hash)? {
+            Some(class) => Some(class),
+            None => return Err(not_declared(args, networks, class_hash)),
+        },
+        None => None,
+    };
+
+    if let Some(info) = api_client.get_class(class_hash)? {
+        debug!(
+            "Class {class_hash} is declared on {}: {info:?}",
+            args.network_url.url
+        );
+        return Ok((info, declared_class));
+    }
+
+    if declared_class.is_some() {
+        warn!(
+            "Class {class_hash} is declared on chain but not yet known to {}, submitting anyway",
+            args.network_url.url
+        );
+        return Ok((ClassInfo::default(), declared_class));
+    }
+    Err(not_declared(args, networks, class_hash))
+}
+
+/// Warn about versions pinned by the project that the local toolchain
+/// doesn't match, and fail if an explicit version contradicts them or if
+/// the submitted Cairo version differs from the compiler of the declared
+/// class, unless --allow-toolchain-mismatch is given.
+fn check_toolchain(
+    args: &VerifyArgs,
+    metadata: &scarb_metadata::Metadata,
+    package_meta: &PackageMetadata,
+    class_info: Option<&ClassInfo>,
+    declared_class: Option<&ContractClass>,
+    class_hash: &ClassHash,
+) -> Result<(), CliError> {
+    let local = local_toolchain(args);
+    let pins = Pins::read(
+        &package_meta.manifest_path,
+        &metadata.workspace.manifest_path,
+    );
+    for mismatch in toolchain::check_pins(&local, &toolchain_overrides(args), &pins) {
+        if mismatch.is_override() && !args.allow_toolchain_mismatch {
+            return Err(ToolchainError::OverrideMismatch(mismatch).into());
+        }
+        warn!("{mismatch}");
+    }
+
+    // Nothing is looked up for dry runs
+    let Some(class_info) = class_info else {
+        return Ok(());
+    };
+    let Some(declared) = declared_compiler_version(class_info, declared_class) else {
+        debug!("Compiler version of class {class_hash} is unknown, skipping the toolchain check");
+        return Ok(());
+    };
+    match toolchain::check_declared(&local, &declared, &pins) {
+        Err(ToolchainError::CompilerMismatch {
+            declared, local, ..
+        }) if args.allow_toolchain_mismatch => {
+            warn!(
+                "Class {class_hash} was compiled with Cairo {declared}, but Cairo {local} is used; submitting anyway due to --allow-toolchain-mismatch"
+            );
+            Ok(())
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Cairo and Scarb versions submitted with the verification, those of the
+/// local installation unless --cairo-version or --scarb-version is given
+fn local_toolchain(args: &VerifyArgs) -> Toolchain {
+    installed_toolchain(args).with_overrides(&toolchain_overrides(args))
+}
+
+/// Versions of the Scarb installation that runs local builds
+fn installed_toolchain(args: &VerifyArgs) -> Toolchain {
+    let metadata = args.path.metadata();
+    Toolchain {
+        cairo: metadata.app_version_info.cairo.version.clone(),
+        scarb: metadata.app_version_info.version.clone(),
+    }
+}
+
+/// Refuse --precheck and --diagnose when the submitted toolchain is
+/// overridden, as their local build wouldn't be representative.
+fn check_local_builds(args: &VerifyArgs) -> Result<(), CliError> {
+    let installed = installed_toolchain(args);
+    let overrides = toolchain_overrides(args);
+    for (enabled, flag) in [(args.precheck, "--precheck"), (args.diagnose, "--diagnose")] {
+        if enabled {
+            toolchain::check_local_build(&installed, &overrides, flag)?;
+        }
+    }
+    Ok(())
+}
+
+fn toolchain_overrides(args: &VerifyArgs) -> Overrides {
+    Overrides {
+        cairo: args.cairo_version.clone(),
+        scarb: args.scarb_version.clone(),
+    }
+}
+
+/// Compiler version recorded in the Sierra program of the declared class,
+/// as read on chain when an RPC endpoint is available and from the explorer
+/// otherwise.
+fn declared_compiler_version(
+    class_info: &ClassInfo,
+    declared_class: Option<&ContractClass>,
+) -> Option<semver::Version> {
+    declared_class
+        .and_then(ContractClass::compiler_version)
+        .or_else(|| class_info.compiler_version())
+}
+
+/// RPC client for --rpc-url, or the RPC endpoint of the selected network
+fn rpc_client(args: &VerifyArgs, network: Option<&NetworkEntry>) -> Option<RpcClient> {
+    args.rpc_url
+        .clone()
+        .or_else(|| network.and_then(|network| network.rpc_url.clone()))
+        .map(RpcClient::new)
+}
+
+/// Hash of the class of the contract deployed at `address`, looked up on
+/// chain when an RPC endpoint is available and on the explorer otherwise.
+fn resolve_address(
+    api_client: &ApiClient,
+    args: &VerifyArgs,
+    networks: &NetworkRegistry,
+    address: &str,
+) -> Result<ClassHash, CliError> {
+    let network = selected_network(networks, args.network.as_deref(), &args.network_url.url);
+    let class_hash = match rpc_client(args, network) {
+        Some(rpc) => rpc.get_class_hash_at(address)?,
+        None => api_client
+            .get_contract(address)?
+            .and_then(|contract| contract.class_hash),
+    };
+    class_hash.ok_or_else(|| CliError::AddressNotFound {
+        address: address.to_string(),
+        network: args
+            .network
+            .clone()
+            .unwrap_or_else(|| args.network_url.url.to_string()),
+    })
+}
+
+/// Ask the chain for the class declared as `class_hash`, warning if the
+/// endpoint serves another chain than the selected network.
+fn declared_on_chain(
+    rpc: &RpcClient,
+    network: Option<&NetworkEntry>,
+    class_hash: &ClassHash,
+) -> Result<Option<DeclaredClass>, CliError> {
+    let chain_id = rpc.chain_id()?;
+    let chain = rpc::chain_name(&chain_id).unwrap_or(chain_id);
+    if let Some((network, expected)) =
+        network.and_then(|network| Some((network, network.expected_chain()?)))
+    {
+        if chain != expected {
+            warn!(
+                "RPC endpoint {} serves {chain}, while network '{}' is {expected}",
+                rpc.url(),
+                network.name
+            );
+        }
+    }
+
+    let declared = rpc.get_declared_class(class_hash)?;
+    debug!(
+        "Class {class_hash} is {}declared on {chain} according to {}",
+        if declared.is_some() { "" } else { "not " },
+        rpc.url()
+    );
+    Ok(declared)
+}
+
+/// How long the other networks are given to tell whether they know a class
+/// missing from the selected network
+const DECLARED_ON_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Error for a class missing from the selected network, naming the other
+/// known networks where it's declared.
+fn not_declared(args: &VerifyArgs, networks: &NetworkRegistry, class_hash: &ClassHash) -> CliError {
+    let declared_on = networks
+        .iter()
+        .filter(|network| network.api_url != args.network_url.url)
+        .filter(|network| {
+            let declared = ApiClient::with_timeout(network.api_url.clone(), DECLARED_ON_TIMEOUT)
+                .and_then(|client| client.get_class(class_hash));
+            match declared {
+                Ok(declared) => declared.is_some(),
+                Err(e) => {
+                    debug!("Could not look up {class_hash} on {}: {e}", network.name);
+                    false
+                }
+            }
+        })
+        .map(|network| network.name.clone())
+        .collect();
+
+    CliError::NotDeclared {
+        class_hash: class_hash.clone(),
+        network: args
+            .network
+            .clone()
+            .unwrap_or_else(|| args.network_url.url.to_string()),
+        declared_on,
+    }
+}
+
+/// Submit every contract listed in the `[tool.voyager]` sections of the
+/// workspace members, one job per contract.
+fn submit_all(
+    api_client: &ApiClient,
+    args: &VerifyArgs,
+    license_info: &license::LicenseInfo,
+    networks: &NetworkRegistry,
+) -> Result<(), CliError> {
+    check_local_builds(args)?;
+    let metadata = args.path.metadata();
+    let sections = voyager::tool_section(metadata)?;
+
+    // Sort by package and contract name so the submission order is stable
+    let mut contracts: BTreeMap<(String, String), voyager::Voyager> = BTreeMap::new();
+    for (package_id, contract_map) in sections {
+        let package_name = metadata
+            .packages
+            .iter()
+            .find(|p| p.id == package_id)
+            .map_or_else(|| package_id.to_string(), |p| p.name.clone());
+        if args
+            .package
+            .as_ref()
+            .is_some_and(|selected| *selected != package_name)
+        {
+            continue;
+        }
+        for (contract_name, contract) in contract_map {
+            contracts.insert((package_name.clone(), contract_name), contract);
+        }
+    }
+
+    if contracts.is_empty() {
+        return Err(CliError::NoTarget);
+    }
+
+    let project_type = determine_project_type(args)?;
+
+    info!(
+        "📋 Found {} contracts in [tool.voyager] sections",
+        contracts.len()
+    );
+
+    let mut entries: Vec<BatchEntry> = vec![];
+    for ((package, contract_name), contract) in contracts {
+        let resolved = match (&contract.class_hash, &contract.address) {
+            (Some(class_hash), _) => Ok(Some(class_hash.clone())),
+            (None, Some(address)) => resolve_address(api_client, args, networks, address).map(Some),
+            (None, None) => Ok(artifact_class_hash(metadata, &package, &contract_name)),
+        };
+        let class_hash = resolved.as_ref().ok().cloned().flatten();
+
+        let outcome = resolved
+            .and_then(|class_hash| {
+                class_hash.ok_or_else(|| CliError::MissingClassHash {
+                    contract: contract_name.clone(),
+                })
+            })
+            .and_then(|class_hash| {
+                let target = VerificationTarget {
+                    contract_name: contract_name.clone(),
+                    class_hash,
+                    address: contract.address.clone(),
+                    package: Some(package.clone()),
+                    contract_path: Some(Utf8PathBuf::try_from(contract.path.clone())?),
+                };
+                submit(
+                    api_client,
+                    args,
+                    &target,
+                    project_type.clone(),
+                    license_info,
+                    networks,
+                )
+            });
+
+        if let Err(e) = &outcome {
+            eprintln!("\n❌ {package}/{contract_name}:\n{e}");
+        }
+
+        entries.push(BatchEntry {
+            package,
+            contract_name,
+            class_hash,
+            address: contract.address,
+            outcome,
+            job: None,
+            watch_error: None,
+        });
+    }
+
+    if args.format == OutputFormat::Text {
+        display_batch_summary(&entries);
+    }
+
+    if args.watch() {
+        let network = selected_network(networks, args.network.as_deref(), &args.network_url.url);
+        for entry in &mut entries {
+            let Ok(Submission {
+                job_id: Some(job_id),
+                ..
+            }) = &entry.outcome
+            else {
+                continue;
+            };
+            if args.format == OutputFormat::Text {
+                println!("\n{}/{}:", entry.package, entry.contract_name);
+            }
+            if let Err(e) = check(
+                api_client,
+                job_id,
+                &args.poll,
+                args.format,
+                network,
+                interrupt_token(),
+                &mut entry.job,
+            ) {
+                eprintln!("{e}");
+                entry.watch_error = Some(e);
+            }
+        }
+    }
+
+    let result = batch_result(&entries);
+
+    if args.format == OutputFormat::Json {
+        let error = result
+            .as_ref()
+            .err()
+            .map(|e| ErrorReport::new(e.error_code(), e));
+        let contracts = entries
+            .into_iter()
+            .map(|entry| entry.into_report(license_info, args.dry_run))
+            .collect();
+        output::print_json(&BatchReport { contracts, error })?;
+    }
+
+    result
+}
+
+/// Overall result of a batch. Failed submissions and failed jobs both count;
+/// when every failure is the same compilation or verification failure, that
+/// error is returned so the exit status tells which. An interruption takes
+/// precedence.
+fn batch_result(entries: &[BatchEntry]) -> Result<(), CliError> {
+    let failures: Vec<&CliError> = entries
+        .iter()
+        .filter_map(|entry| entry.outcome.as_ref().err().or(entry.watch_error.as_ref()))
+        .collect();
+    let Some(first) = failures.first() else {
+        return Ok(());
+    };
+    // Once interrupted, the remaining jobs weren't waited for
+    if let Some(job_id) = failures.iter().find_map(|e| match e {
+        CliError::Api(ApiClientError::Cancelled(job_id)) => Some(job_id),
+        _ => None,
+    }) {
+        return Err(ApiClientError::Cancelled(job_id.clone()).into());
+    }
+
+    let verification_error = |e: &CliError| match e {
+        CliError::Api(ApiClientError::Verify(error)) => Some(mem::discriminant(error)),
+        _ => None,
+    };
+    if let CliError::Api(ApiClientError::Verify(shared)) = first {
+        if failures
+            .iter()
+            .all(|e| verification_error(e) == Some(mem::discriminant(shared)))
+        {
+            return Err(ApiClientError::from(shared.clone()).into());
+        }
+    }
+    Err(CliError::BatchFailed {
+        failed: failures.len(),
+        total: entries.len(),
+    })
+}
+
+/// Compute the class hash of a contract from its Scarb build artifact, if the
+/// project has been built.
+fn artifact_class_hash(
+    metadata: &scarb_metadata::Metadata,
+    package_name: &str,
+    contract_name: &str,
+) -> Option<ClassHash> {
+    let package = metadata.packages.iter().find(|p| p.name == package_name)?;
+    let artifact = class_hash::find_artifact(metadata, package, contract_name)?;
+
+    match class_hash::compute_class_hash(&artifact) {
+        Ok(class_hash) => {
+            info!("🔢 Computed class hash {class_hash} for '{contract_name}' from {artifact}");
+            Some(class_hash)
+        }
+        Err(e) => {
+            warn!("Failed to compute class hash from {artifact}: {e}");
+            None
+        }
+    }
+}
+
+/// List the contracts of every package and report them in the requested
+/// format.
+fn list(args: &ListArgs) -> Result<(), CliError> {
+    let result = list_packages(args);
+    match args.format {
+        OutputFormat::Text => display_packages(&result?),
+        OutputFormat::Json => {
+            let (packages, error) = match result {
+                Ok(packages) => (packages, None),
+                Err(e) => (vec![], Some(e)),
+            };
+            output::print_json(&ListReport {
+                packages,
+                error: error.as_ref().map(|e| ErrorReport::new(e.error_code(), e)),
+            })?;
+            if let Some(e) = error {
+                return Err(e);
+            }
+        }
+    }
     Ok(())
 }
 
-fn submit(
-    api_client: &ApiClient,
-    args: &VerifyArgs,
-    license_info: &license::LicenseInfo,
-) -> Result<String, CliError> {
-    info!("🚀 Starting verification for project at: {}", args.path);
-
-    // Determine project type early in the process
-    let project_type = determine_project_type(args)?;
+fn list_packages(args: &ListArgs) -> Result<Vec<PackageReport>, CliError> {
+    let metadata = args.path.metadata();
+    let root = args.path.root_dir();
+    let relative = |path: &Utf8Path| path.strip_prefix(root).unwrap_or(path).to_string();
 
-    // Log the selected build tool
-    match project_type {
-        ProjectType::Dojo => info!("Using sozo build for Dojo project"),
-        ProjectType::Scarb => info!("Using scarb build for Scarb project"),
-        ProjectType::Auto => unreachable!("Auto should be resolved by now"),
-    }
+    let mut packages: Vec<PackageMetadata> = vec![];
+    resolver::gather_packages(metadata, &mut packages)?;
 
-    let metadata = args.path.metadata();
+    packages
+        .iter()
+        .map(|package| {
+            let sources = resolver::package_sources(package)?;
+            let contracts = contracts::find_contracts(package, &sources)?
+                .into_iter()
+                .map(|contract| {
+                    let artifact = class_hash::find_artifact(metadata, package, &contract.name);
+                    let class_hash = artifact.as_ref().and_then(|artifact| {
+                        class_hash::compute_class_hash(artifact)
+                            .inspect_err(|e| {
+                                warn!("Failed to compute class hash from {artifact}: {e}");
+                            })
+                            .ok()
+                    });
+                    ContractReport {
+                        module_path: contract.module_path,
+                        file: relative(&contract.file),
+                        kind: contract.kind,
+                        class_hash: class_hash.map(|hash| hash.to_string()),
+                        artifact: artifact.as_deref().map(relative),
+                        name: contract.name,
+                    }
+                })
+                .collect();
+
+            Ok(PackageReport {
+                name: package.name.clone(),
+                version: package.version.to_string(),
+                manifest_path: relative(&package.manifest_path),
+                contract_targets: package
+                    .targets
+                    .iter()
+                    .filter(|target| target.kind == "starknet-contract")
+                    .map(|target| target.name.clone())
+                    .collect(),
+                contracts,
+            })
+        })
+        .collect()
+}
 
-    // Determine test_files setting - default to true for Dojo projects
-    let include_test_files = match project_type {
-        ProjectType::Dojo => {
-            if !args.test_files {
-                info!("🧪 Including test files by default for Dojo project");
-            }
-            true
+fn display_packages(packages: &[PackageReport]) {
+    for package in packages {
+        println!(
+            "\n📦 {} {} ({})",
+            package.name.bold(),
+            package.version,
+            package.manifest_path
+        );
+        if package.contract_targets.is_empty() {
+            println!("   {}", "no starknet-contract target".yellow());
+        } else {
+            println!(
+                "   starknet-contract targets: {}",
+                package.contract_targets.join(", ")
+            );
         }
-        _ => args.test_files,
-    };
 
-    // Gather packages and sources
-    let packages = gather_packages_and_validate(metadata, args)?;
-    let sources = collect_source_files(metadata, &packages, include_test_files)?;
+        if package.contracts.is_empty() {
+            println!("   no contract modules found");
+            continue;
+        }
+        let module_width = package
+            .contracts
+            .iter()
+            .map(|contract| contract.module_path.len())
+            .max()
+            .unwrap_or(0);
+        let file_width = package
+            .contracts
+            .iter()
+            .map(|contract| contract.file.len())
+            .max()
+            .unwrap_or(0);
+        for contract in &package.contracts {
+            let class_hash = contract
+                .class_hash
+                .as_ref()
+                .map_or_else(|| "not built".dimmed(), |hash| hash.green());
+            println!(
+                "   {:<module_width$}  {:<file_width$}  {class_hash}",
+                contract.module_path, contract.file
+            );
+        }
+    }
+    println!();
+}
 
-    // Prepare project structure
-    let (file_infos, package_meta, contract_file, project_dir_path) =
-        prepare_project_for_verification(args, metadata, &packages, sources)?;
+fn display_batch_summary(entries: &[BatchEntry]) {
+    let rows: Vec<[String; 4]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.package.clone(),
+                entry.contract_name.clone(),
+                entry
+                    .class_hash
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), ToString::to_string),
+                match &entry.outcome {
+                    Ok(submission) if submission.already_verified() => {
+                        "already verified".to_string()
+                    }
+                    Ok(submission) => submission
+                        .job_id
+                        .clone()
+                        .unwrap_or_else(|| "dry-run".to_string()),
+                    Err(e) => format!("failed ({})", e.error_code()),
+                },
+            ]
+        })
+        .collect();
 
-    // Log verification info
-    log_verification_info(args, metadata, &file_infos, &contract_file, license_info);
+    let header = ["PACKAGE", "CONTRACT", "CLASS HASH", "JOB ID"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
 
-    // Execute verification unless dry run is requested
-    if !args.dry_run {
-        let context = VerificationContext {
-            project_type,
-            project_dir_path,
-            contract_file,
-            package_meta,
-            file_infos,
+    println!();
+    println!(
+        "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+        header[0].bold(),
+        header[1].bold(),
+        header[2].bold(),
+        header[3].bold(),
+        w0 = widths[0],
+        w1 = widths[1],
+        w2 = widths[2],
+    );
+    for (row, entry) in rows.iter().zip(entries) {
+        let job = if entry.outcome.is_ok() {
+            row[3].green()
+        } else {
+            row[3].red()
         };
-        return execute_verification(api_client, args, context, license_info);
+        println!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {job}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
     }
-
-    info!("Dry run mode: collected files for verification but skipping submission due to --dry-run flag");
-    Ok("dry-run".to_string())
+    println!();
 }
 
+/// Gather the packages of the project and the name of the one to verify,
+/// given with --package or inferred from the contract name.
 fn gather_packages_and_validate(
-    metadata: &scarb_metadata::Metadata,
     args: &VerifyArgs,
-) -> Result<Vec<PackageMetadata>, CliError> {
+    metadata: &scarb_metadata::Metadata,
+    target: &VerificationTarget,
+    include_test_files: bool,
+) -> Result<(Vec<PackageMetadata>, String), CliError> {
     let mut packages: Vec<PackageMetadata> = vec![];
     resolver::gather_packages(metadata, &mut packages)?;
 
-    // Filter packages based on --package argument
-    let filtered_packages: Vec<&PackageMetadata> = if let Some(package_id) = &args.package {
-        packages.iter().filter(|p| p.name == *package_id).collect()
-    } else {
-        packages.iter().collect()
+    let package = match &target.package {
+        Some(name) => workspace_member(metadata, name)?,
+        None => infer_package(
+            args,
+            metadata,
+            &packages,
+            &target.contract_name,
+            include_test_files,
+        )?,
     };
 
-    // Validate package selection
-    if filtered_packages.is_empty() {
-        if let Some(package_name) = &args.package {
-            let available_packages: Vec<String> = packages.iter().map(|p| p.name.clone()).collect();
-            return Err(CliError::from(errors::MissingContract::new(
-                package_name.clone(),
-                available_packages,
-            )));
-        }
-    }
+    Ok((packages, package))
+}
 
-    // Check workspace requirements
-    let workspace_manifest = &metadata.workspace.manifest_path;
-    let manifest_path = voyager::manifest_path(metadata);
-    let is_workspace = workspace_manifest != manifest_path && metadata.workspace.members.len() > 1;
+/// Check that --package names a workspace member. Path dependencies are
+/// gathered along with the members, but can only be submitted with them.
+fn workspace_member(metadata: &scarb_metadata::Metadata, name: &str) -> Result<String, CliError> {
+    let is_member = metadata
+        .packages
+        .iter()
+        .any(|p| p.name == name && metadata.workspace.members.contains(&p.id));
+    if is_member {
+        Ok(name.to_string())
+    } else {
+        Err(errors::MissingPackage::new(name, metadata).into())
+    }
+}
 
-    if args.package.is_none() && is_workspace {
-        let available_packages: Vec<String> = packages.iter().map(|p| p.name.clone()).collect();
-        return Err(CliError::from(errors::MissingContract::new(
-            "Workspace project detected - use --package argument".to_string(),
-            available_packages,
-        )));
+/// Name of the workspace member defining `contract_name`, asking the user
+/// when several members do.
+fn infer_package(
+    args: &VerifyArgs,
+    metadata: &scarb_metadata::Metadata,
+    packages: &[PackageMetadata],
+    contract_name: &str,
+    include_test_files: bool,
+) -> Result<String, CliError> {
+    let mut candidates: Vec<String> = vec![];
+    let mut available: Vec<String> = vec![];
+    for package in packages
+        .iter()
+        .filter(|package| metadata.workspace.members.contains(&package.id))
+    {
+        let (sources, _) =
+            collect_source_files(args, std::slice::from_ref(package), include_test_files)?;
+        let contracts = contracts::find_contracts(package, &sources)?;
+        if contracts
+            .iter()
+            .any(|contract| contract.name == contract_name)
+        {
+            candidates.push(package.name.clone());
+        }
+        available.extend(contracts.into_iter().map(|contract| contract.name));
     }
 
-    Ok(packages)
+    match candidates.as_slice() {
+        [] => Err(CliError::from(errors::MissingContract::in_sources(
+            contract_name.to_string(),
+            available.into_iter().unique().collect(),
+        ))),
+        [package] => {
+            info!("📦 Found '{contract_name}' in package '{package}'");
+            Ok(package.clone())
+        }
+        _ => {
+            let prompt = format!(
+                "'{contract_name}' is defined in several packages, which one should be verified?"
+            );
+            let selection = args.prompter().select(&Choice {
+                prompt: &prompt,
+                options: candidates.clone(),
+                argument: "--package <NAME>",
+                fallback: Fallback::Fail,
+            })?;
+            Ok(candidates.swap_remove(selection))
+        }
+    }
 }
 
+/// Collect the sources of every package, along with the files that were
+/// only included because of an include glob.
 fn collect_source_files(
-    _metadata: &scarb_metadata::Metadata,
+    args: &VerifyArgs,
     packages: &[PackageMetadata],
     include_test_files: bool,
-) -> Result<Vec<Utf8PathBuf>, CliError> {
+) -> Result<(Vec<Utf8PathBuf>, HashSet<Utf8PathBuf>), CliError> {
+    let globs = args.source_globs();
     let mut sources: Vec<Utf8PathBuf> = vec![];
+    let mut glob_included = HashSet::new();
+    for package in packages {
+        for decision in resolver::explain_package_sources(package, include_test_files, &globs)? {
+            if !decision.included {
+                continue;
+            }
+            if matches!(decision.reason, Reason::IncludeGlob { .. }) {
+                glob_included.insert(decision.path.clone());
+            }
+            sources.push(decision.path);
+        }
+    }
+    Ok((sources, glob_included))
+}
+
+/// Explain the decision for every candidate file, including the manifests
+/// and lock file added on top of the package sources.
+fn explain_source_files(
+    args: &VerifyArgs,
+    packages: &[PackageMetadata],
+    workspace_files: Vec<FileDecision>,
+    include_test_files: bool,
+) -> Result<Vec<FileReport>, CliError> {
+    let mut decisions: Vec<FileDecision> = vec![];
     for package in packages {
-        let mut package_sources =
-            resolver::package_sources_with_test_files(package, include_test_files)?;
-        sources.append(&mut package_sources);
+        decisions.append(&mut resolver::explain_package_sources(
+            package,
+            include_test_files,
+            &args.source_globs(),
+        )?);
+    }
+    decisions.extend(workspace_files);
+
+    let lock_file_path = args.path.root_dir().join("Scarb.lock");
+    if lock_file_path.exists() {
+        decisions.push(if args.lock_file() {
+            FileDecision::include(lock_file_path, Reason::LockFile)
+        } else {
+            FileDecision::exclude(lock_file_path, Reason::LockFile)
+        });
+    }
+
+    // The same file may be a candidate of several packages, keep the first
+    // decision that includes it
+    let mut explained: BTreeMap<Utf8PathBuf, FileDecision> = BTreeMap::new();
+    for decision in decisions {
+        match explained.get(&decision.path) {
+            Some(existing) if existing.included || !decision.included => {}
+            _ => {
+                explained.insert(decision.path.clone(), decision);
+            }
+        }
+    }
+
+    let root = args.path.root_dir();
+    Ok(explained
+        .into_values()
+        .map(|decision| FileReport {
+            path: decision
+                .path
+                .strip_prefix(root)
+                .unwrap_or(&decision.path)
+                .to_string(),
+            included: decision.included,
+            reason: decision.to_string(),
+        })
+        .collect())
+}
+
+fn display_explanation(files: &[FileReport]) {
+    let included = files.iter().filter(|file| file.included).count();
+    println!(
+        "\n📋 Candidate files ({included} included, {} excluded):",
+        files.len() - included
+    );
+
+    let width = files.iter().map(|file| file.path.len()).max().unwrap_or(0);
+    for file in files {
+        if file.included {
+            println!("  {} {:<width$}  {}", "✓".green(), file.path, file.reason);
+        } else {
+            println!(
+                "  {} {:<width$}  {}",
+                "✗".red(),
+                file.path,
+                file.reason.dimmed()
+            );
+        }
     }
-    Ok(sources)
+    println!();
 }
 
 fn prepare_project_for_verification(
     args: &VerifyArgs,
+    target: &VerificationTarget,
     metadata: &scarb_metadata::Metadata,
     packages: &[PackageMetadata],
     sources: Vec<Utf8PathBuf>,
+    glob_included: &HashSet<Utf8PathBuf>,
+    project_root: &Utf8Path,
 ) -> Result<(Vec<FileInfo>, PackageMetadata, String, String), CliError> {
-    let prefix = resolver::biggest_common_prefix(&sources, args.path.root_dir());
+    let prefix = resolver::biggest_common_prefix(&sources, project_root);
 
     // Build file map
-    let files = build_file_map(&sources, &prefix, metadata, args)?;
+    let files = build_file_map(&sources, &prefix, metadata, args, glob_included)?;
 
     // Filter packages and get the target package
-    let filtered_packages: Vec<&PackageMetadata> = if let Some(package_id) = &args.package {
+    let filtered_packages: Vec<&PackageMetadata> = if let Some(package_id) = &target.package {
         packages.iter().filter(|p| p.name == *package_id).collect()
     } else {
         packages.iter().collect()
@@ -330,7 +1818,17 @@ fn prepare_project_for_verification(
         .ok_or_else(|| CliError::NoTarget)?;
 
     // Find contract file
-    let contract_file_path = find_contract_file(package_meta, &sources, &args.contract_name)?;
+    let contract_file_path = match &target.contract_path {
+        Some(path) if package_meta.root.join(path).exists() => package_meta.root.join(path),
+        Some(path) => {
+            warn!(
+                "Contract path '{path}' for '{}' not found in package '{}', looking up the contract module",
+                target.contract_name, package_meta.name
+            );
+            find_contract_file(package_meta, &sources, &target.contract_name)?
+        }
+        None => find_contract_file(package_meta, &sources, &target.contract_name)?,
+    };
     let contract_file =
         contract_file_path
             .strip_prefix(&prefix)
@@ -340,7 +1838,7 @@ fn prepare_project_for_verification(
             })?;
 
     // Prepare project directory path
-    let project_dir_path = prepare_project_dir_path(args, &prefix)?;
+    let project_dir_path = prepare_project_dir_path(project_root, &prefix)?;
 
     // Convert to FileInfo
     let file_infos = convert_to_file_info(files);
@@ -358,6 +1856,7 @@ fn build_file_map(
     prefix: &Utf8Path,
     metadata: &scarb_metadata::Metadata,
     args: &VerifyArgs,
+    glob_included: &HashSet<Utf8PathBuf>,
 ) -> Result<HashMap<String, Utf8PathBuf>, CliError> {
     let mut files: HashMap<String, Utf8PathBuf> = sources
         .iter()
@@ -377,17 +1876,22 @@ fn build_file_map(
     add_lock_file_if_requested(&mut files, args, prefix)?;
 
     // Validate file sizes
-    validate_file_sizes(&files)?;
+    validate_file_sizes(&files, glob_included)?;
 
     Ok(files)
 }
 
-fn validate_file_sizes(files: &HashMap<String, Utf8PathBuf>) -> Result<(), CliError> {
+fn validate_file_sizes(
+    files: &HashMap<String, Utf8PathBuf>,
+    glob_included: &HashSet<Utf8PathBuf>,
+) -> Result<(), CliError> {
     const MAX_FILE_SIZE: usize = 1024 * 1024 * 20; // 20MB limit
 
     for path in files.values() {
-        // Validate file type
-        validate_file_type(path)?;
+        // Validate file type, files matched by an include glob were asked for explicitly
+        if !glob_included.contains(path) {
+            validate_file_type(path)?;
+        }
 
         // Validate file size
         if let Ok(metadata) = std::fs::metadata(path) {
@@ -448,6 +1952,11 @@ fn add_manifest_files(
     metadata: &scarb_metadata::Metadata,
     prefix: &Utf8Path,
 ) -> Result<(), CliError> {
+    // Workspace manifests are part of the sources when they are needed
+    if is_workspace(metadata) {
+        return Ok(());
+    }
+
     let manifest_path = voyager::manifest_path(metadata);
     let manifest = manifest_path
         .strip_prefix(prefix)
@@ -457,39 +1966,83 @@ fn add_manifest_files(
         })?;
 
     files.insert(manifest.to_string(), manifest_path.clone());
-
-    // Handle workspace manifests
-    add_workspace_manifest_if_needed(files, metadata, prefix)?;
-
     Ok(())
 }
 
-fn add_workspace_manifest_if_needed(
-    files: &mut HashMap<String, Utf8PathBuf>,
+/// Whether the project is a workspace of several packages
+fn is_workspace(metadata: &scarb_metadata::Metadata) -> bool {
+    metadata.workspace.members.len() > 1
+}
+
+/// Decide on the workspace manifest. It's only submitted when it belongs to
+/// one of `packages`, one of them inherits from it or the lock file, which
+/// lives next to it, is requested. Its members are then narrowed down to
+/// `packages`, see [`narrowed_workspace_manifest`].
+fn workspace_manifest_decisions(
+    args: &VerifyArgs,
     metadata: &scarb_metadata::Metadata,
-    prefix: &Utf8Path,
-) -> Result<(), CliError> {
-    let workspace_manifest = &metadata.workspace.manifest_path;
-    let manifest_path = voyager::manifest_path(metadata);
+    packages: &[PackageMetadata],
+) -> Result<Vec<FileDecision>, CliError> {
+    if !is_workspace(metadata) {
+        return Ok(vec![]);
+    }
 
-    let is_workspace = workspace_manifest != manifest_path && metadata.workspace.members.len() > 1;
-
-    if is_workspace {
-        let workspace_manifest_rel =
-            workspace_manifest
-                .strip_prefix(prefix)
-                .map_err(|_| CliError::StripPrefix {
-                    path: workspace_manifest.clone(),
-                    prefix: prefix.to_path_buf(),
-                })?;
-        debug!("Including workspace root manifest: {workspace_manifest}");
-        files.insert(
-            workspace_manifest_rel.to_string(),
-            workspace_manifest.clone(),
-        );
+    let workspace_manifest = metadata.workspace.manifest_path.clone();
+    let mut needed = args.lock_file()
+        || packages
+            .iter()
+            .any(|package| package.manifest_path == workspace_manifest);
+    for package in packages {
+        needed = needed || resolver::inherits_from_workspace(package)?;
+    }
+    if !needed {
+        debug!("Leaving out workspace manifest {workspace_manifest}, no package inherits from it");
+        return Ok(vec![FileDecision::exclude(
+            workspace_manifest,
+            Reason::UnusedWorkspaceManifest,
+        )]);
     }
 
-    Ok(())
+    debug!("Including workspace root manifest: {workspace_manifest}");
+    Ok(vec![FileDecision::include(
+        workspace_manifest,
+        Reason::WorkspaceManifest,
+    )])
+}
+
+/// Write the workspace manifest with its members narrowed down to the
+/// workspace members among `packages`.
+fn narrowed_workspace_manifest(
+    metadata: &scarb_metadata::Metadata,
+    packages: &[PackageMetadata],
+) -> Result<NamedTempFile, CliError> {
+    let members = packages
+        .iter()
+        .filter(|package| metadata.workspace.members.contains(&package.id))
+        .collect_vec();
+    let manifest =
+        resolver::narrowed_workspace_manifest(&metadata.workspace.manifest_path, &members)?;
+    debug!(
+        "Submitting {} with members: {}",
+        metadata.workspace.manifest_path,
+        members.iter().map(|member| &member.name).join(", ")
+    );
+
+    let write = || -> std::io::Result<NamedTempFile> {
+        let file = tempfile::Builder::new()
+            .prefix("Scarb")
+            .suffix(".toml")
+            .tempfile()?;
+        fs::write(file.path(), manifest)?;
+        Ok(file)
+    };
+    write().map_err(|e| {
+        resolver::Error::IoError {
+            path: metadata.workspace.manifest_path.to_string(),
+            error: e.to_string(),
+        }
+        .into()
+    })
 }
 
 fn add_lock_file_if_requested(
@@ -497,7 +2050,7 @@ fn add_lock_file_if_requested(
     args: &VerifyArgs,
     prefix: &Utf8Path,
 ) -> Result<(), CliError> {
-    if args.lock_file {
+    if args.lock_file() {
         let lock_file_path = args.path.root_dir().join("Scarb.lock");
         if lock_file_path.exists() {
             let lock_file_rel =
@@ -516,53 +2069,51 @@ fn add_lock_file_if_requested(
     Ok(())
 }
 
+/// Source file of the contract module named `contract_name` in the sources
+/// of the package.
 fn find_contract_file(
     package_meta: &PackageMetadata,
     sources: &[Utf8PathBuf],
     contract_name: &str,
 ) -> Result<Utf8PathBuf, CliError> {
-    // First try to find a file that matches the contract name
-    let contract_specific_paths = vec![
-        format!("src/{}.cairo", contract_name),
-        format!("src/systems/{}.cairo", contract_name),
-        format!("src/contracts/{}.cairo", contract_name),
-    ];
-
-    for path in contract_specific_paths {
-        let full_path = package_meta.root.join(&path);
-        if full_path.exists() {
-            return Ok(full_path);
-        }
-    }
-
-    // Find the main source file for the package (conventionally src/lib.cairo or src/main.cairo)
-    let possible_main_paths = vec!["src/lib.cairo", "src/main.cairo"];
-
-    for path in possible_main_paths {
-        let full_path = package_meta.root.join(path);
-        if full_path.exists() {
-            return Ok(full_path);
-        }
-    }
-
-    // If we can't find a main file, use the first source file in the package
-    let contract_file_path = sources
+    let contracts = contracts::find_contracts(package_meta, sources)?;
+    let mut matching = contracts
         .iter()
-        .filter(|path| path.starts_with(&package_meta.root))
-        .find(|path| path.extension() == Some("cairo"))
-        .cloned()
-        .ok_or(CliError::NoTarget)?;
+        .filter(|contract| contract.name == contract_name);
+
+    let Some(contract) = matching.next() else {
+        let available = contracts
+            .iter()
+            .map(|contract| contract.name.clone())
+            .unique()
+            .collect();
+        return Err(CliError::from(errors::MissingContract::in_sources(
+            contract_name.to_string(),
+            available,
+        )));
+    };
+    for other in matching {
+        warn!(
+            "Contract '{contract_name}' is defined both as {} and {}, using {}",
+            contract.module_path, other.module_path, contract.file
+        );
+    }
 
-    Ok(contract_file_path)
+    debug!(
+        "Found contract {} ({}) in {}",
+        contract.module_path, contract.kind, contract.file
+    );
+    Ok(contract.file.clone())
 }
 
-fn prepare_project_dir_path(args: &VerifyArgs, prefix: &Utf8Path) -> Result<String, CliError> {
-    let project_dir_path = args
-        .path
-        .root_dir()
+fn prepare_project_dir_path(
+    project_root: &Utf8Path,
+    prefix: &Utf8Path,
+) -> Result<String, CliError> {
+    let project_dir_path = project_root
         .strip_prefix(prefix)
         .map_err(|_| CliError::StripPrefix {
-            path: args.path.root_dir().clone(),
+            path: project_root.to_path_buf(),
             prefix: prefix.to_path_buf(),
         })
         // backend expects this for cwd
@@ -587,19 +2138,55 @@ fn convert_to_file_info(files: HashMap<String, Utf8PathBuf>) -> Vec<FileInfo> {
         .collect_vec()
 }
 
-fn log_verification_info(
+/// Check the total size of the payload against the limit of the selected
+/// network.
+fn check_payload_size(
     args: &VerifyArgs,
-    metadata: &scarb_metadata::Metadata,
+    networks: &NetworkRegistry,
+    packages: &[PackageMetadata],
+    file_infos: &[FileInfo],
+) -> Result<(), CliError> {
+    let network = selected_network(networks, args.network.as_deref(), &args.network_url.url);
+    let limit = network.map_or(payload::MAX_PAYLOAD_SIZE, |network| {
+        network.max_payload_size
+    });
+
+    let files = file_infos
+        .iter()
+        .map(|file| PayloadFile {
+            name: file.name.clone(),
+            // The innermost package root containing the file
+            package: packages
+                .iter()
+                .filter(|package| file.path.starts_with(&package.root))
+                .max_by_key(|package| package.root.as_str().len())
+                .map(|package| package.name.clone()),
+            size: fs::metadata(&file.path).map_or(0, |metadata| metadata.len()),
+        })
+        .collect_vec();
+
+    let total = payload::check_budget(&files, limit, network.map(|network| network.name.as_str()))?;
+    debug!(
+        "📦 Payload size: {} of {}",
+        payload::format_size(total),
+        payload::format_size(limit)
+    );
+    Ok(())
+}
+
+fn log_verification_info(
+    target: &VerificationTarget,
+    toolchain: &Toolchain,
     file_infos: &[FileInfo],
     contract_file: &str,
     license_info: &license::LicenseInfo,
 ) {
-    let cairo_version = &metadata.app_version_info.cairo.version;
-    let scarb_version = &metadata.app_version_info.version;
+    let cairo_version = &toolchain.cairo;
+    let scarb_version = &toolchain.scarb;
 
     info!(
         "Verifying contract: {} from {}",
-        args.contract_name, contract_file
+        target.contract_name, contract_file
     );
     info!("licensed with: {}", license_info.display_string());
     info!("using cairo: {cairo_version} and scarb {scarb_version}");
@@ -612,12 +2199,33 @@ fn log_verification_info(
 fn execute_verification(
     api_client: &ApiClient,
     args: &VerifyArgs,
-    context: VerificationContext,
+    target: &VerificationTarget,
+    context: &VerificationContext,
     license_info: &license::LicenseInfo,
 ) -> Result<String, CliError> {
-    let metadata = args.path.metadata();
-    let cairo_version = metadata.app_version_info.cairo.version.clone();
-    let scarb_version = metadata.app_version_info.version.clone();
+    let project_meta = project_metadata_info(args, target, context);
+
+    api_client
+        .verify_class(
+            &target.class_hash,
+            Some(license_info.display_string().to_string()),
+            &target.contract_name,
+            project_meta,
+            &context.file_infos,
+        )
+        .map_err(CliError::from)
+}
+
+/// Form fields describing the project, as sent with the payload files
+fn project_metadata_info(
+    args: &VerifyArgs,
+    target: &VerificationTarget,
+    context: &VerificationContext,
+) -> ProjectMetadataInfo {
+    let Toolchain {
+        cairo: cairo_version,
+        scarb: scarb_version,
+    } = local_toolchain(args);
 
     // Create project metadata with build tool information
     debug!(
@@ -655,26 +2263,18 @@ fn execute_verification(
     let project_meta = ProjectMetadataInfo::new(
         cairo_version,
         scarb_version,
-        context.project_dir_path,
-        context.contract_file,
-        context.package_meta.name,
-        context.project_type,
+        context.project_dir_path.clone(),
+        context.contract_file.clone(),
+        context.package_meta.name.clone(),
+        context.project_type.clone(),
         dojo_version,
-    );
+    )
+    .with_address(target.address.clone());
     debug!(
         "Created ProjectMetadataInfo with build_tool: {}, dojo_version: {:?}",
         project_meta.build_tool, project_meta.dojo_version
     );
-
-    api_client
-        .verify_class(
-            &args.class_hash,
-            Some(license_info.display_string().to_string()),
-            &args.contract_name,
-            project_meta,
-            &context.file_infos,
-        )
-        .map_err(CliError::from)
+    project_meta
 }
 
 fn extract_dojo_version(project_dir_path: &str) -> Option<String> {
@@ -733,6 +2333,22 @@ fn extract_dojo_version(project_dir_path: &str) -> Option<String> {
     None
 }
 
+fn display_status_update(update: &StatusUpdate<'_>) {
+    let elapsed = update.elapsed.as_secs();
+    match update.previous {
+        None => println!(
+            "⏳ Job {}: {} ({elapsed}s)",
+            update.job.job_id(),
+            update.job.status()
+        ),
+        Some(previous) => println!(
+            "⏳ Job {}: {previous} → {} ({elapsed}s)",
+            update.job.job_id(),
+            update.job.status()
+        ),
+    }
+}
+
 fn format_timestamp(timestamp: f64) -> String {
     let duration = Duration::from_secs_f64(timestamp);
     if let Some(datetime) = UNIX_EPOCH.checked_add(duration) {
@@ -743,9 +2359,62 @@ fn format_timestamp(timestamp: f64) -> String {
     }
 }
 
-fn check(api_client: &ApiClient, job_id: &str) -> Result<VerificationJob, CliError> {
-    let status = poll_verification_status(api_client, job_id).map_err(CliError::from)?;
+/// Spread the requests of concurrent CI jobs polling the same service
+const POLL_JITTER: f64 = 0.1;
+
+/// Token cancelled by Ctrl-C, so that waiting for a job stops cleanly. A
+/// second Ctrl-C exits right away.
+fn interrupt_token() -> &'static CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        let token = CancellationToken::new();
+        let handler_token = token.clone();
+        if let Err(e) = ctrlc::set_handler(move || {
+            if handler_token.is_cancelled() {
+                std::process::exit(ExitStatus::Interrupted as i32);
+            }
+            handler_token.cancel();
+        }) {
+            debug!("Could not install the Ctrl-C handler: {e}");
+        }
+        token
+    })
+}
+
+/// Poll `job_id` until it finishes or `cancellation` is cancelled, see
+/// [`interrupt_token`]. `last_seen` receives the latest job state even when
+/// the verification fails.
+fn check(
+    api_client: &ApiClient,
+    job_id: &str,
+    poll: &PollArgs,
+    format: OutputFormat,
+    network: Option<&NetworkEntry>,
+    cancellation: &CancellationToken,
+    last_seen: &mut Option<VerificationJob>,
+) -> Result<VerificationJob, CliError> {
+    let status = Poller::new(api_client)
+        .with_interval(poll.interval())
+        .with_timeout(poll.timeout())
+        .with_jitter(POLL_JITTER)
+        .with_cancellation(cancellation.clone())
+        .on_status(|update| {
+            if format == OutputFormat::Text {
+                display_status_update(update);
+            }
+            *last_seen = Some(update.job.clone());
+        })
+        .poll(job_id)
+        .map_err(CliError::from)?;
+
+    if format == OutputFormat::Text {
+        display_job(&status, network);
+    }
+
+    Ok(status)
+}
 
+fn display_job(status: &VerificationJob, network: Option<&NetworkEntry>) {
     match status.status() {
         VerifyJobStatus::Success => {
             println!("\n✅ Verification successful!");
@@ -774,7 +2443,10 @@ fn check(api_client: &ApiClient, job_id: &str) -> Result<VerificationJob, CliErr
             if let Some(updated) = status.updated_timestamp() {
                 println!("Last updated: {}", format_timestamp(updated));
             }
-            println!("\nThe contract is now verified and visible on Voyager at https://voyager.online/class/{} .", status.class_hash());
+            match network.and_then(|network| network.explorer_url(status.class_hash())) {
+                Some(url) => println!("\nThe contract is now verified and visible at {url} ."),
+                None => println!("\nThe contract is now verified."),
+            }
         }
         VerifyJobStatus::Fail => {
             println!("\n❌ Verification failed!");
@@ -846,8 +2518,6 @@ fn check(api_client: &ApiClient, job_id: &str) -> Result<VerificationJob, CliErr
             println!("\nUse the same command to check progress later.");
         }
     }
-
-    Ok(status)
 }
 
 fn determine_project_type(args: &VerifyArgs) -> Result<ProjectType, CliError> {
@@ -870,17 +2540,20 @@ fn determine_project_type(args: &VerifyArgs) -> Result<ProjectType, CliError> {
                     Ok(ProjectType::Scarb)
                 }
                 ProjectType::Auto => {
-                    // Fallback to interactive prompt
-                    let options = vec![
-                        "Regular Scarb project (uses scarb build)",
-                        "Dojo project (uses sozo build)",
-                    ];
-
-                    let selection = Select::new()
-                        .with_prompt("What type of project are you verifying?")
-                        .items(&options)
-                        .default(0)
-                        .interact()?;
+                    let prompter = args.prompter();
+                    // Without markers of a Dojo project, build it with scarb
+                    let selection = prompter.select(&Choice {
+                        prompt: "What type of project are you verifying?",
+                        options: vec![
+                            "Regular Scarb project (uses scarb build)".to_string(),
+                            "Dojo project (uses sozo build)".to_string(),
+                        ],
+                        argument: "--project-type=scarb or --project-type=dojo",
+                        fallback: Fallback::Default(0),
+                    })?;
+                    if let Some(reason) = prompter.non_interactive_reason() {
+                        warn!("Could not detect the project type, using scarb ({reason}). Pass --project-type=dojo for Dojo projects");
+                    }
 
                     match selection {
                         0 => Ok(ProjectType::Scarb),
@@ -920,3 +2593,260 @@ fn validate_dojo_project(project: &args::Project) -> Result<(), CliError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use reqwest::Url;
+    use verifier::errors::RequestFailure;
+
+    /// Batch entry whose job, watched on `server`, ends with `status`
+    fn watched_entry(server: &mut mockito::Server, contract_name: &str, status: u8) -> BatchEntry {
+        let job_id = format!("job-{contract_name}");
+        server
+            .mock("GET", format!("/class-verify/job/{job_id}").as_str())
+            .with_body(format!(
+                r#"{{"job_id": "{job_id}", "status": {status}, "status_description": null, "class_hash": "0x123"}}"#
+            ))
+            .create();
+
+        let api_client = ApiClient::new(Url::parse(&server.url()).unwrap()).unwrap();
+        let poll = PollArgs {
+            timeout: 5,
+            poll_interval: 1,
+        };
+        let mut job = None;
+        let watch_error = check(
+            &api_client,
+            &job_id,
+            &poll,
+            OutputFormat::Json,
+            None,
+            &CancellationToken::new(),
+            &mut job,
+        )
+        .err();
+
+        BatchEntry {
+            package: "app".to_string(),
+            contract_name: contract_name.to_string(),
+            class_hash: Some(ClassHash::new("0x123").unwrap()),
+            address: None,
+            outcome: Ok(Submission {
+                job_id: Some(job_id),
+                files: vec![],
+                class_info: None,
+                declared_class: None,
+                explanation: None,
+                package: Some("app".to_string()),
+                context: None,
+            }),
+            job,
+            watch_error,
+        }
+    }
+
+    #[test]
+    fn test_check_stops_when_cancelled() {
+        let mut server = mockito::Server::new();
+        // Status 0 is Submitted, the job never finishes
+        server
+            .mock("GET", "/class-verify/job/job-1")
+            .with_body(
+                r#"{"job_id": "job-1", "status": 0, "status_description": null, "class_hash": "0x123"}"#,
+            )
+            .create();
+
+        let api_client = ApiClient::new(Url::parse(&server.url()).unwrap()).unwrap();
+        let poll = PollArgs {
+            timeout: 60,
+            poll_interval: 1,
+        };
+        let cancellation = CancellationToken::new();
+        let interrupt = cancellation.clone();
+        let interrupter = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            interrupt.cancel();
+        });
+
+        let mut job = None;
+        let error = check(
+            &api_client,
+            "job-1",
+            &poll,
+            OutputFormat::Json,
+            None,
+            &cancellation,
+            &mut job,
+        )
+        .unwrap_err();
+        interrupter.join().unwrap();
+        assert_eq!(error.error_code(), "E039");
+        assert_eq!(error.exit_status(), ExitStatus::Interrupted);
+        assert!(job.is_some());
+    }
+
+    #[test]
+    fn test_batch_counts_failed_jobs() {
+        let mut server = mockito::Server::new();
+        // Status 2 is CompileFailed, 3 Fail and 4 Success
+        let entries = vec![
+            watched_entry(&mut server, "Token", 3),
+            watched_entry(&mut server, "Vault", 3),
+        ];
+        let error = batch_result(&entries).unwrap_err();
+        assert_eq!(error.error_code(), "E005");
+        assert!(matches!(
+            error.exit_status(),
+            ExitStatus::VerificationFailure
+        ));
+
+        let entries = vec![
+            watched_entry(&mut server, "Token", 3),
+            watched_entry(&mut server, "Vault", 2),
+            watched_entry(&mut server, "Pool", 4),
+        ];
+        let error = batch_result(&entries).unwrap_err();
+        assert!(matches!(
+            error,
+            CliError::BatchFailed {
+                failed: 2,
+                total: 3
+            }
+        ));
+
+        let entries = vec![watched_entry(&mut server, "Pool", 4)];
+        assert!(batch_result(&entries).is_ok());
+    }
+
+    #[test]
+    fn test_package_must_be_workspace_member() {
+        let package = |name: &str| {
+            serde_json::json!({
+                "id": format!("{name} 0.1.0 (path+file:///project/{name}/Scarb.toml)"),
+                "name": name,
+                "version": "0.1.0",
+                "source": format!("path+file:///project/{name}/Scarb.toml"),
+                "manifest_path": format!("/project/{name}/Scarb.toml"),
+                "root": format!("/project/{name}"),
+                "dependencies": [],
+                "targets": [],
+                "manifest_metadata": {}
+            })
+        };
+        // `utils` is a path dependency of `app`, outside of the workspace
+        let metadata: scarb_metadata::Metadata = serde_json::from_value(serde_json::json!({
+            "version": 1,
+            "app_exe": null,
+            "app_version_info": {
+                "version": "2.11.4",
+                "commit_info": null,
+                "cairo": { "version": "2.11.4", "commit_info": null }
+            },
+            "target_dir": null,
+            "workspace": {
+                "manifest_path": "/project/app/Scarb.toml",
+                "root": "/project/app",
+                "members": ["app 0.1.0 (path+file:///project/app/Scarb.toml)"]
+            },
+            "packages": [package("app"), package("utils")],
+            "compilation_units": []
+        }))
+        .unwrap();
+
+        assert_eq!(workspace_member(&metadata, "app").unwrap(), "app");
+        let error = workspace_member(&metadata, "utils").unwrap_err();
+        assert_eq!(error.error_code(), "E001");
+        assert_eq!(error.exit_status(), ExitStatus::Project);
+        assert!(matches!(
+            error,
+            CliError::MissingPackage(errors::MissingPackage { ref available, .. })
+                if available == &["app".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_include_glob_skips_file_type_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let logo = root.join("logo.svg");
+        std::fs::write(&logo, "<svg/>").unwrap();
+        let files = HashMap::from([("logo.svg".to_string(), logo.clone())]);
+
+        let error = validate_file_sizes(&files, &HashSet::new()).unwrap_err();
+        assert_eq!(error.error_code(), "E024");
+        let glob_included = HashSet::from([logo.clone()]);
+        assert!(validate_file_sizes(&files, &glob_included).is_ok());
+
+        // The size limit still applies to them
+        std::fs::File::create(&logo)
+            .unwrap()
+            .set_len(1024 * 1024 * 21)
+            .unwrap();
+        let error = validate_file_sizes(&files, &glob_included).unwrap_err();
+        assert_eq!(error.error_code(), "E019");
+    }
+
+    #[test]
+    fn test_exit_status_per_error_class() {
+        let url = Url::parse("https://api.voyager.online/beta").unwrap();
+        let cases: Vec<(CliError, ExitStatus)> = vec![
+            (
+                CliError::BatchFailed {
+                    failed: 1,
+                    total: 2,
+                },
+                ExitStatus::Failure,
+            ),
+            (CliError::DiagnoseWithoutRpc, ExitStatus::Usage),
+            (
+                ApiClientError::JobNotFound("job".to_string()).into(),
+                ExitStatus::Usage,
+            ),
+            (CliError::NoTarget, ExitStatus::Project),
+            (
+                CliError::Rpc(RpcError::Request {
+                    url: url.to_string(),
+                    error: "connection refused".to_string(),
+                }),
+                ExitStatus::Network,
+            ),
+            (
+                ApiClientError::Failure(RequestFailure::new(
+                    url.clone(),
+                    reqwest::StatusCode::BAD_GATEWAY,
+                    "bad gateway",
+                ))
+                .into(),
+                ExitStatus::Network,
+            ),
+            (
+                ApiClientError::Failure(RequestFailure::new(
+                    url,
+                    reqwest::StatusCode::PAYLOAD_TOO_LARGE,
+                    "payload too large",
+                ))
+                .into(),
+                ExitStatus::Failure,
+            ),
+            (
+                ApiClientError::from(VerificationError::CompilationFailure(String::new())).into(),
+                ExitStatus::CompilationFailure,
+            ),
+            (
+                ApiClientError::from(VerificationError::VerificationFailure(String::new())).into(),
+                ExitStatus::VerificationFailure,
+            ),
+            (ApiClientError::InProgress.into(), ExitStatus::InProgress),
+            (
+                ApiClientError::Cancelled("job".to_string()).into(),
+                ExitStatus::Interrupted,
+            ),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(error.exit_status(), expected, "{error}");
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Registry of the networks selectable with `--network`.
+//!
+//! The built-in entries cover the public Voyager deployments. Configuration
+//! files can add networks, e.g. a private explorer deployment or a devnet,
+//! and override fields of existing ones under `[networks.<name>]`.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+use url::Url;
+
+use crate::payload::{SizeSetting, MAX_PAYLOAD_SIZE};
+
+/// Placeholder replaced by the class hash in explorer URL templates
+pub const CLASS_HASH_PLACEHOLDER: &str = "{class_hash}";
+
+#[derive(Debug, Error)]
+pub enum NetworkError {
+    #[error("[E048] Invalid {field} for network '{network}': {error}\n\nSuggestions:\n  • Use a full URL including the scheme, e.g. https://api.example.com/beta\n  • Check the [networks.{network}] section of your configuration")]
+    InvalidUrl {
+        network: String,
+        field: &'static str,
+        error: String,
+    },
+
+    #[error("[E049] Network '{0}' has no api-url\n\nSuggestions:\n  • Add api-url = \"https://...\" to [networks.{0}]\n  • Only the fields of built-in networks (mainnet, sepolia, dev) can be omitted")]
+    MissingApiUrl(String),
+
+    #[error("[E050] Explorer URL template '{template}' for network '{network}' doesn't contain {CLASS_HASH_PLACEHOLDER}\n\nSuggestions:\n  • Use a template like https://explorer.example.com/class/{CLASS_HASH_PLACEHOLDER}")]
+    InvalidExplorerTemplate { network: String, template: String },
+
+    #[error("[E052] Invalid max-payload-size '{size}' for network '{network}'\n\nSuggestions:\n  • Use a number of bytes or a size with a unit, e.g. max-payload-size = \"20MB\"\n  • Supported units: B, KB, MB, GB")]
+    InvalidPayloadSize { network: String, size: String },
+}
+
+impl NetworkError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::InvalidUrl { .. } => "E048",
+            Self::MissingApiUrl(_) => "E049",
+            Self::InvalidExplorerTemplate { .. } => "E050",
+            Self::InvalidPayloadSize { .. } => "E052",
+        }
+    }
+}
+
+/// A network as configured under `[networks.<name>]`. Every field is
+/// optional so that built-in networks can be partially overridden.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub api_url: Option<String>,
+    pub explorer_url_template: Option<String>,
+    pub rpc_url: Option<String>,
+    pub max_payload_size: Option<SizeSetting>,
+}
+
+impl NetworkConfig {
+    /// Override the fields of `self` with those set in `other`.
+    pub fn merge(&mut self, other: &Self) {
+        if other.api_url.is_some() {
+            self.api_url.clone_from(&other.api_url);
+        }
+        if other.explorer_url_template.is_some() {
+            self.explorer_url_template
+                .clone_from(&other.explorer_url_template);
+        }
+        if other.rpc_url.is_some() {
+            self.rpc_url.clone_from(&other.rpc_url);
+        }
+        if other.max_payload_size.is_some() {
+            self.max_payload_size.clone_from(&other.max_payload_size);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkEntry {
+    pub name: String,
+    /// Verification API endpoint
+    pub api_url: Url,
+    /// Explorer page of a class, with [`CLASS_HASH_PLACEHOLDER`]
+    pub explorer_url_template: Option<String>,
+    /// Starknet JSON-RPC endpoint
+    pub rpc_url: Option<Url>,
+    /// Largest payload accepted by the verification API, in bytes
+    pub max_payload_size: u64,
+}
+
+impl NetworkEntry {
+    /// Explorer page of `class_hash`, if the network has an explorer.
+    #[must_use]
+    pub fn explorer_url(&self, class_hash: &str) -> Option<String> {
+        self.explorer_url_template
+            .as_ref()
+            .map(|template| template.replace(CLASS_HASH_PLACEHOLDER, class_hash))
+    }
+
+    /// Chain served by the network, for the built-in public networks.
+    #[must_use]
+    pub fn expected_chain(&self) -> Option<&'static str> {
+        match self.name.as_str() {
+            "mainnet" => Some("SN_MAIN"),
+            "sepolia" => Some("SN_SEPOLIA"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkRegistry {
+    networks: Vec<NetworkEntry>,
+}
+
+impl Default for NetworkRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl NetworkRegistry {
+    /// Names of the built-in networks
+    pub const BUILTIN: [&'static str; 3] = ["mainnet", "sepolia", "dev"];
+
+    /// The public Voyager deployments.
+    #[must_use]
+    #[allow(clippy::expect_used)]
+    pub fn builtin() -> Self {
+        let network = |name: &str, api_url: &str, explorer: &str| NetworkEntry {
+            name: name.to_string(),
+            api_url: Url::parse(api_url).expect("built-in API URL is valid"),
+            explorer_url_template: Some(format!("{explorer}/class/{CLASS_HASH_PLACEHOLDER}")),
+            rpc_url: None,
+            max_payload_size: MAX_PAYLOAD_SIZE,
+        };
+        Self {
+            networks: vec![
+                network(
+                    "mainnet",
+                    "https://api.voyager.online/beta",
+                    "https://voyager.online",
+                ),
+                network(
+                    "sepolia",
+                    "https://sepolia-api.voyager.online/beta",
+                    "https://sepolia.voyager.online",
+                ),
+                network(
+                    "dev",
+                    "https://dev-api.voyager.online/beta",
+                    "https://dev.voyager.online",
+                ),
+            ],
+        }
+    }
+
+    /// Apply configured networks on top of the built-in ones. Entries for
+    /// an existing name override its fields, other entries are added.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a URL or payload size is invalid, a new network has
+    /// no API URL or an explorer template lacks the class hash placeholder.
+    pub fn with_config(networks: &BTreeMap<String, NetworkConfig>) -> Result<Self, NetworkError> {
+        let mut registry = Self::builtin();
+        for (name, config) in networks {
+            let existing = registry.networks.iter().position(|n| n.name == *name);
+
+            let api_url = match (&config.api_url, existing) {
+                (Some(url), _) => parse_url(name, "api-url", url)?,
+                (None, Some(index)) => registry.networks[index].api_url.clone(),
+                (None, None) => return Err(NetworkError::MissingApiUrl(name.clone())),
+            };
+            let explorer_url_template = match (&config.explorer_url_template, existing) {
+                (Some(template), _) => {
+                    if !template.contains(CLASS_HASH_PLACEHOLDER) {
+                        return Err(NetworkError::InvalidExplorerTemplate {
+                            network: name.clone(),
+                            template: template.clone(),
+                        });
+                    }
+                    Some(template.clone())
+                }
+                (None, Some(index)) => registry.networks[index].explorer_url_template.clone(),
+                (None, None) => None,
+            };
+            let rpc_url = match (&config.rpc_url, existing) {
+                (Some(url), _) => Some(parse_url(name, "rpc-url", url)?),
+                (None, Some(index)) => registry.networks[index].rpc_url.clone(),
+                (None, None) => None,
+            };
+            let max_payload_size = match (&config.max_payload_size, existing) {
+                (Some(size), _) => {
+                    size.bytes()
+                        .ok_or_else(|| NetworkError::InvalidPayloadSize {
+                            network: name.clone(),
+                            size: size.to_string(),
+                        })?
+                }
+                (None, Some(index)) => registry.networks[index].max_payload_size,
+                (None, None) => MAX_PAYLOAD_SIZE,
+            };
+
+            let entry = NetworkEntry {
+                name: name.clone(),
+                api_url,
+                explorer_url_template,
+                rpc_url,
+                max_payload_size,
+            };
+            match existing {
+                Some(index) => registry.networks[index] = entry,
+                None => registry.networks.push(entry),
+            }
+        }
+        Ok(registry)
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&NetworkEntry> {
+        self.networks.iter().find(|network| network.name == name)
+    }
+
+    /// Network whose API is served at `api_url`, used when `--url` is given
+    /// instead of `--network`.
+    #[must_use]
+    pub fn find_by_api_url(&self, api_url: &Url) -> Option<&NetworkEntry> {
+        self.networks
+            .iter()
+            .find(|network| network.api_url == *api_url)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NetworkEntry> {
+        self.networks.iter()
+    }
+
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        self.networks.iter().map(|n| n.name.clone()).collect()
+    }
+}
+
+fn parse_url(network: &str, field: &'static str, url: &str) -> Result<Url, NetworkError> {
+    Url::parse(url).map_err(|e| NetworkError::InvalidUrl {
+        network: network.to_string(),
+        field,
+        error: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_explorer_links() {
+        let registry = NetworkRegistry::builtin();
+        assert_eq!(registry.names(), NetworkRegistry::BUILTIN.map(String::from));
+        assert_eq!(
+            registry
+                .get("mainnet")
+                .unwrap()
+                .explorer_url("0x1")
+                .unwrap(),
+            "https://voyager.online/class/0x1"
+        );
+        assert_eq!(
+            registry
+                .get("sepolia")
+                .unwrap()
+                .explorer_url("0x1")
+                .unwrap(),
+            "https://sepolia.voyager.online/class/0x1"
+        );
+
+        let url = Url::parse("https://dev-api.voyager.online/beta").unwrap();
+        assert_eq!(registry.find_by_api_url(&url).unwrap().name, "dev");
+    }
+
+    #[test]
+    fn test_config_adds_and_overrides_networks() {
+        let networks = BTreeMap::from([
+            (
+                "private".to_string(),
+                NetworkConfig {
+                    api_url: Some("https://verifier.internal/api".to_string()),
+                    explorer_url_template: Some(
+                        "https://explorer.internal/classes/{class_hash}?tab=code".to_string(),
+                    ),
+                    rpc_url: Some("http://127.0.0.1:5050/rpc".to_string()),
+                    max_payload_size: Some(SizeSetting::Text("20MB".to_string())),
+                },
+            ),
+            (
+                "sepolia".to_string(),
+                NetworkConfig {
+                    rpc_url: Some("https://rpc.sepolia.example".to_string()),
+                    ..NetworkConfig::default()
+                },
+            ),
+        ]);
+        let registry = NetworkRegistry::with_config(&networks).unwrap();
+
+        let private = registry.get("private").unwrap();
+        assert_eq!(private.api_url.as_str(), "https://verifier.internal/api");
+        assert_eq!(
+            private.explorer_url("0xabc").unwrap(),
+            "https://explorer.internal/classes/0xabc?tab=code"
+        );
+        assert_eq!(private.max_payload_size, 20 * 1024 * 1024);
+
+        let sepolia = registry.get("sepolia").unwrap();
+        assert_eq!(
+            sepolia.api_url.as_str(),
+            "https://sepolia-api.voyager.online/beta"
+        );
+        assert_eq!(
+            sepolia.rpc_url.as_ref().unwrap().as_str(),
+            "https://rpc.sepolia.example/"
+        );
+        assert_eq!(sepolia.max_payload_size, MAX_PAYLOAD_SIZE);
+        assert_eq!(registry.names().len(), 4);
+    }
+
+    #[test]
+    fn test_invalid_network_config() {
+        let missing_api = BTreeMap::from([("devnet".to_string(), NetworkConfig::default())]);
+        let error = NetworkRegistry::with_config(&missing_api).unwrap_err();
+        assert_eq!(error.error_code(), "E049");
+
+        let bad_template = BTreeMap::from([(
+            "mainnet".to_string(),
+            NetworkConfig {
+                explorer_url_template: Some("https://explorer.example/class".to_string()),
+                ..NetworkConfig::default()
+            },
+        )]);
+        let error = NetworkRegistry::with_config(&bad_template).unwrap_err();
+        assert_eq!(error.error_code(), "E050");
+
+        let bad_url = BTreeMap::from([(
+            "devnet".to_string(),
+            NetworkConfig {
+                api_url: Some("not a url".to_string()),
+                ..NetworkConfig::default()
+            },
+        )]);
+        let error = NetworkRegistry::with_config(&bad_url).unwrap_err();
+        assert_eq!(error.error_code(), "E048");
+
+        let bad_size = BTreeMap::from([(
+            "mainnet".to_string(),
+            NetworkConfig {
+                max_payload_size: Some(SizeSetting::Text("a lot".to_string())),
+                ..NetworkConfig::default()
+            },
+        )]);
+        let error = NetworkRegistry::with_config(&bad_size).unwrap_err();
+        assert_eq!(error.error_code(), "E052");
+    }
+}
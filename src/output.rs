@@ -0,0 +1,116 @@
+//! Machine-readable reports printed with `--format json`.
+
+use serde::Serialize;
+use std::fmt::Display;
+use verifier::{
+    api::{ClassInfo, VerificationJob},
+    contracts::ContractKind,
+    diagnose::ClassDiff,
+};
+
+/// Error code and message of a failed command
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub code: String,
+    pub message: String,
+}
+
+impl ErrorReport {
+    pub fn new(code: &str, error: &impl Display) -> Self {
+        Self {
+            code: code.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Outcome of a single contract verification
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    pub contract_name: String,
+    pub class_hash: String,
+    /// Address given with --address, whose class is verified
+    pub address: Option<String>,
+    pub package: Option<String>,
+    /// SPDX identifier sent with the submission, `NONE` if unlicensed
+    pub license: String,
+    pub dry_run: bool,
+    /// Submission was skipped because the class is already verified
+    pub already_verified: bool,
+    pub job_id: Option<String>,
+    /// Payload file names, relative to the submitted project root
+    pub files: Vec<String>,
+    /// Every candidate file with its decision, only present with `--explain`
+    pub explain: Option<Vec<FileReport>>,
+    /// Class as found on the network before submitting
+    pub class: Option<ClassInfo>,
+    /// Last observed state of the job, only present with `--watch`
+    pub job: Option<VerificationJob>,
+    /// Local build compared with the declared class, only present with
+    /// `--diagnose` after a failed verification
+    pub diagnosis: Option<ClassDiff>,
+    pub error: Option<ErrorReport>,
+}
+
+/// Decision taken for a candidate payload file
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    /// Path relative to the project root
+    pub path: String,
+    pub included: bool,
+    pub reason: String,
+}
+
+/// Outcome of `verify --all`
+#[derive(Debug, Serialize)]
+pub struct BatchReport {
+    pub contracts: Vec<VerifyReport>,
+    pub error: Option<ErrorReport>,
+}
+
+/// Outcome of `status`
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub job_id: String,
+    pub job: Option<VerificationJob>,
+    pub error: Option<ErrorReport>,
+}
+
+/// Outcome of `list`
+#[derive(Debug, Serialize)]
+pub struct ListReport {
+    pub packages: Vec<PackageReport>,
+    pub error: Option<ErrorReport>,
+}
+
+/// A package with the contracts found in its sources
+#[derive(Debug, Serialize)]
+pub struct PackageReport {
+    pub name: String,
+    pub version: String,
+    /// Manifest path relative to the project root
+    pub manifest_path: String,
+    /// Names of the `starknet-contract` targets
+    pub contract_targets: Vec<String>,
+    pub contracts: Vec<ContractReport>,
+}
+
+/// A contract module found in the sources
+#[derive(Debug, Serialize)]
+pub struct ContractReport {
+    pub name: String,
+    pub module_path: String,
+    /// Source file relative to the project root
+    pub file: String,
+    pub kind: ContractKind,
+    /// Computed from the build artifact, `None` if the project isn't built
+    pub class_hash: Option<String>,
+    /// Build artifact relative to the project root
+    pub artifact: Option<String>,
+}
+
+/// Print `report` as pretty JSON on stdout.
+pub fn print_json(report: &impl Serialize) -> Result<(), serde_json::Error> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
@@ -0,0 +1,285 @@
+//! Size budget of the verification payload.
+//!
+//! The verification API rejects payloads larger than 10MB. The total size of
+//! the files is checked against the limit of the selected network before
+//! uploading, and a breakdown by package and directory is reported when it's
+//! exceeded so that the largest offenders can be left out.
+
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt};
+use thiserror::Error;
+
+/// Payload limit of the public verification API
+pub const MAX_PAYLOAD_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Number of directories and files named in a breakdown
+const TOP_ENTRIES: usize = 5;
+
+/// Package of files outside of every package, e.g. the workspace manifest
+pub const WORKSPACE_ENTRY: &str = "(workspace)";
+
+#[derive(Debug, Error)]
+pub enum PayloadError {
+    #[error("[E053] Payload of {} exceeds the {} limit{}\n\n{breakdown}\nSuggestions:\n  • Leave out the largest files with --exclude <GLOB> or an exclude entry in [tool.voyager]\n  • Don't submit test files unless they are needed to build the contract\n  • Raise the limit with max-payload-size in [networks.<name>] if the server accepts larger payloads", format_size(breakdown.total), format_size(*limit), network.as_ref().map(|name| format!(" of network '{name}'")).unwrap_or_default())]
+    TooLarge {
+        limit: u64,
+        network: Option<String>,
+        breakdown: Breakdown,
+    },
+}
+
+impl PayloadError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "E053",
+        }
+    }
+}
+
+/// A size as written in configuration files, either a number of bytes or a
+/// string with a unit such as `"20MB"`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SizeSetting {
+    Bytes(u64),
+    Text(String),
+}
+
+impl SizeSetting {
+    /// Size in bytes, `None` if the text isn't a valid size.
+    #[must_use]
+    pub fn bytes(&self) -> Option<u64> {
+        match self {
+            Self::Bytes(bytes) => Some(*bytes),
+            Self::Text(text) => parse_size(text),
+        }
+    }
+}
+
+impl fmt::Display for SizeSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes(bytes) => write!(f, "{bytes}"),
+            Self::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// Parse a size like `10MB`, `512 KB` or `1048576`. Units are binary, `1KB`
+/// being 1024 bytes, as used by the verification API.
+#[must_use]
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" | "K" | "KIB" => 1024,
+        "MB" | "M" | "MIB" => 1024 * 1024,
+        "GB" | "G" | "GIB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+/// Human readable size, e.g. `10.0 MB`
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// A file of the payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadFile {
+    /// Path relative to the payload root
+    pub name: String,
+    /// Package the file belongs to, `None` for workspace files
+    pub package: Option<String>,
+    pub size: u64,
+}
+
+/// Sizes of a payload grouped by package and directory, largest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown {
+    pub total: u64,
+    pub packages: Vec<(String, u64)>,
+    pub directories: Vec<(String, u64)>,
+    pub largest_files: Vec<(String, u64)>,
+}
+
+impl Breakdown {
+    #[must_use]
+    pub fn new(files: &[PayloadFile]) -> Self {
+        let mut packages: BTreeMap<String, u64> = BTreeMap::new();
+        let mut directories: BTreeMap<String, u64> = BTreeMap::new();
+        for file in files {
+            let package = file.package.as_deref().unwrap_or(WORKSPACE_ENTRY);
+            *packages.entry(package.to_string()).or_default() += file.size;
+
+            let directory = file
+                .name
+                .rsplit_once('/')
+                .map_or(".", |(directory, _)| directory);
+            *directories.entry(directory.to_string()).or_default() += file.size;
+        }
+
+        let mut largest_files: Vec<(String, u64)> = files
+            .iter()
+            .map(|file| (file.name.clone(), file.size))
+            .collect();
+        sort_largest_first(&mut largest_files);
+        largest_files.truncate(TOP_ENTRIES);
+
+        let mut directories: Vec<(String, u64)> = directories.into_iter().collect();
+        sort_largest_first(&mut directories);
+        directories.truncate(TOP_ENTRIES);
+
+        let mut packages: Vec<(String, u64)> = packages.into_iter().collect();
+        sort_largest_first(&mut packages);
+
+        Self {
+            total: files.iter().map(|file| file.size).sum(),
+            packages,
+            directories,
+            largest_files,
+        }
+    }
+}
+
+/// Sort by decreasing size, then by name so the order is stable
+fn sort_largest_first(entries: &mut [(String, u64)]) {
+    entries.sort_by(|(a_name, a_size), (b_name, b_size)| {
+        b_size.cmp(a_size).then_with(|| a_name.cmp(b_name))
+    });
+}
+
+impl fmt::Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            ("By package", &self.packages),
+            ("Largest directories", &self.directories),
+            ("Largest files", &self.largest_files),
+        ];
+        for (title, entries) in sections {
+            writeln!(f, "{title}:")?;
+            for (name, size) in entries {
+                writeln!(f, "  {:>10}  {name}", format_size(*size))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the total size of `files` against `limit`, returning the total.
+///
+/// # Errors
+///
+/// Returns `Err` with a breakdown of the payload if it exceeds `limit`.
+pub fn check_budget(
+    files: &[PayloadFile],
+    limit: u64,
+    network: Option<&str>,
+) -> Result<u64, PayloadError> {
+    let total: u64 = files.iter().map(|file| file.size).sum();
+    if total > limit {
+        return Err(PayloadError::TooLarge {
+            limit,
+            network: network.map(ToString::to_string),
+            breakdown: Breakdown::new(files),
+        });
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, package: Option<&str>, size: u64) -> PayloadFile {
+        PayloadFile {
+            name: name.to_string(),
+            package: package.map(ToString::to_string),
+            size,
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1048576"), Some(1024 * 1024));
+        assert_eq!(parse_size("10MB"), Some(MAX_PAYLOAD_SIZE));
+        assert_eq!(parse_size("512 kb"), Some(512 * 1024));
+        assert_eq!(parse_size("1GiB"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("10 parsecs"), None);
+        assert_eq!(parse_size("MB"), None);
+        assert_eq!(SizeSetting::Bytes(42).bytes(), Some(42));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(MAX_PAYLOAD_SIZE), "10.0 MB");
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let files = [
+            file("Scarb.toml", None, 100),
+            file("vault/src/lib.cairo", Some("vault"), 300),
+            file("vault/src/tests/data.json", Some("vault"), 5000),
+            file("shared/src/lib.cairo", Some("shared"), 200),
+        ];
+        let breakdown = Breakdown::new(&files);
+        assert_eq!(breakdown.total, 5600);
+        assert_eq!(
+            breakdown.packages,
+            vec![
+                ("vault".to_string(), 5300),
+                ("shared".to_string(), 200),
+                (WORKSPACE_ENTRY.to_string(), 100),
+            ]
+        );
+        assert_eq!(
+            breakdown.directories[0],
+            ("vault/src/tests".to_string(), 5000)
+        );
+        assert_eq!(breakdown.directories[3], (".".to_string(), 100));
+        assert_eq!(
+            breakdown.largest_files[0],
+            ("vault/src/tests/data.json".to_string(), 5000)
+        );
+    }
+
+    #[test]
+    fn test_check_budget() {
+        let files = [
+            file("src/lib.cairo", Some("app"), 600),
+            file("src/big.json", Some("app"), 600),
+        ];
+        assert_eq!(check_budget(&files, 2000, None).unwrap(), 1200);
+
+        let error = check_budget(&files, 1000, Some("mainnet")).unwrap_err();
+        assert_eq!(error.error_code(), "E053");
+        let message = error.to_string();
+        assert!(message.contains("exceeds the 1000 B limit of network 'mainnet'"));
+        assert!(message.contains("src/big.json"));
+    }
+}
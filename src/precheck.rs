@@ -0,0 +1,279 @@
+//! Local build check of a verification payload before it is submitted.
+//!
+//! The exact set of files that would be uploaded is copied into a temporary
+//! directory and built there with the same command the verification service
+//! runs (`scarb --release build`, or `sozo --release build` for Dojo
+//! projects). The class hash of the resulting artifact is then compared with
+//! the declared class hash, so missing files and hash mismatches are caught
+//! before anything is sent.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::{debug, info};
+use scarb_metadata::PackageMetadata;
+use std::{fs, process::Command};
+use tempfile::TempDir;
+use thiserror::Error;
+
+use crate::{
+    api::FileInfo,
+    class_hash::{self, ArtifactError, ClassHash},
+    project::ProjectType,
+};
+
+/// Profile used by the verification service when building the payload
+const BUILD_PROFILE: &str = "release";
+
+#[derive(Debug, Error)]
+pub enum PrecheckError {
+    #[error("[E033] Failed to stage verification payload in '{path}': {error}\n\nSuggestions:\n  • Check that the temporary directory is writable\n  • Ensure there is enough disk space available")]
+    Stage { path: Utf8PathBuf, error: String },
+
+    #[error("[E034] Could not run '{tool}': {error}\n\nSuggestions:\n  • Make sure '{tool}' is installed and available in PATH\n  • Run without --precheck to skip the local build")]
+    ToolUnavailable { tool: String, error: String },
+
+    #[error("[E035] Local build of the verification payload failed\n\n{output}\n\nSuggestions:\n  • The payload is probably missing a file the build needs\n  • Use --lock-file or --test-files if the build depends on them\n  • Run '{tool} --release build' in your project to reproduce")]
+    BuildFailed { tool: String, output: String },
+
+    #[error("[E036] No artifact for contract '{contract_name}' found in '{profile_dir}'\n\nSuggestions:\n  • Check that --contract-name matches the contract module name\n  • Ensure the package has a [[target.starknet-contract]] section\n  • Verify the contract builds with '{tool} --release build'")]
+    ArtifactNotFound {
+        contract_name: String,
+        profile_dir: Utf8PathBuf,
+        tool: String,
+    },
+
+    #[error("[E037] Class hash mismatch: payload builds to {actual}, expected {expected}\n\nSuggestions:\n  • Check that --class-hash refers to this contract\n  • Make sure the compiler settings match those used for deployment\n  • Put deployment compiler settings under [profile.release]\n  • Check that your local Cairo/Scarb versions match the declared class")]
+    HashMismatch {
+        expected: ClassHash,
+        actual: ClassHash,
+    },
+
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+}
+
+impl PrecheckError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::Stage { .. } => "E033",
+            Self::ToolUnavailable { .. } => "E034",
+            Self::BuildFailed { .. } => "E035",
+            Self::ArtifactNotFound { .. } => "E036",
+            Self::HashMismatch { .. } => "E037",
+            Self::Artifact(e) => e.error_code(),
+        }
+    }
+}
+
+/// Artifact of a payload built in a temporary directory, which is removed
+/// when this is dropped
+#[derive(Debug)]
+pub struct Build {
+    _dir: TempDir,
+    pub artifact: Utf8PathBuf,
+}
+
+/// Build the payload in isolation and compare its class hash with `expected`.
+///
+/// `project_dir_path` is the directory, relative to the payload root, that
+/// the build runs in.
+///
+/// # Errors
+///
+/// Returns `Err` if the payload can't be staged or built, or if the built
+/// class hash differs from `expected`.
+pub fn run(
+    files: &[FileInfo],
+    project_dir_path: &str,
+    project_type: &ProjectType,
+    package: &PackageMetadata,
+    contract_name: &str,
+    expected: &ClassHash,
+) -> Result<ClassHash, PrecheckError> {
+    let build = build_payload(
+        files,
+        project_dir_path,
+        project_type,
+        package,
+        contract_name,
+    )?;
+    check_artifact(&build.artifact, expected)
+}
+
+/// Build the payload in isolation, returning the artifact of
+/// `contract_name`.
+///
+/// # Errors
+///
+/// Returns `Err` if the payload can't be staged or built, or if the build
+/// has no artifact for the contract.
+pub fn build_payload(
+    files: &[FileInfo],
+    project_dir_path: &str,
+    project_type: &ProjectType,
+    package: &PackageMetadata,
+    contract_name: &str,
+) -> Result<Build, PrecheckError> {
+    let temp_dir = TempDir::new().map_err(|e| PrecheckError::Stage {
+        path: Utf8PathBuf::from("<temp dir>"),
+        error: e.to_string(),
+    })?;
+    let root =
+        Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).map_err(|e| PrecheckError::Stage {
+            path: Utf8PathBuf::from("<temp dir>"),
+            error: e.to_string(),
+        })?;
+
+    info!(
+        "🔨 Building {} payload files locally in {root}",
+        files.len()
+    );
+    stage_payload(files, &root)?;
+
+    let project_dir = root.join(project_dir_path);
+    let tool = project_type.build_tool();
+    build(&project_dir, tool)?;
+
+    let profile_dir = project_dir.join("target").join(BUILD_PROFILE);
+    let artifact =
+        class_hash::find_artifact_in(&profile_dir, package, contract_name).ok_or_else(|| {
+            PrecheckError::ArtifactNotFound {
+                contract_name: contract_name.to_string(),
+                profile_dir: profile_dir.clone(),
+                tool: tool.to_string(),
+            }
+        })?;
+
+    Ok(Build {
+        _dir: temp_dir,
+        artifact,
+    })
+}
+
+/// Copy every payload file to its relative name under `root`.
+///
+/// # Errors
+///
+/// Returns `Err` if a directory can't be created or a file can't be copied.
+pub fn stage_payload(files: &[FileInfo], root: &Utf8Path) -> Result<(), PrecheckError> {
+    for file in files {
+        let destination = root.join(&file.name);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(|e| PrecheckError::Stage {
+                path: parent.to_path_buf(),
+                error: e.to_string(),
+            })?;
+        }
+        debug!("Staging {} -> {destination}", file.path.display());
+        fs::copy(&file.path, &destination).map_err(|e| PrecheckError::Stage {
+            path: destination.clone(),
+            error: e.to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Compare the class hash of a built artifact with the expected one.
+///
+/// # Errors
+///
+/// Returns `Err` if the artifact can't be hashed or the hashes differ.
+pub fn check_artifact(
+    artifact: &Utf8Path,
+    expected: &ClassHash,
+) -> Result<ClassHash, PrecheckError> {
+    let actual = class_hash::compute_class_hash(artifact)?;
+    debug!("Artifact {artifact} has class hash {actual}");
+
+    if actual.matches(expected) {
+        Ok(actual)
+    } else {
+        Err(PrecheckError::HashMismatch {
+            expected: expected.clone(),
+            actual,
+        })
+    }
+}
+
+fn build(project_dir: &Utf8Path, tool: &str) -> Result<(), PrecheckError> {
+    debug!("Running '{tool} --{BUILD_PROFILE} build' in {project_dir}");
+    let output = Command::new(tool)
+        .arg(format!("--{BUILD_PROFILE}"))
+        .arg("build")
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PrecheckError::ToolUnavailable {
+            tool: tool.to_string(),
+            error: e.to_string(),
+        })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(PrecheckError::BuildFailed {
+            tool: tool.to_string(),
+            output: format!("{}\n{}", stdout.trim(), stderr.trim())
+                .trim()
+                .to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/hello_starknet.contract_class.json"
+    );
+
+    #[test]
+    fn test_stage_payload_preserves_relative_names() {
+        let source = TempDir::new().unwrap();
+        let lib = source.path().join("lib.cairo");
+        fs::write(&lib, "mod contract;").unwrap();
+        let manifest = source.path().join("Scarb.toml");
+        fs::write(&manifest, "[package]").unwrap();
+
+        let files = vec![
+            FileInfo {
+                name: "my_project/src/lib.cairo".to_string(),
+                path: lib,
+            },
+            FileInfo {
+                name: "my_project/Scarb.toml".to_string(),
+                path: manifest,
+            },
+        ];
+
+        let staged = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(staged.path().to_path_buf()).unwrap();
+        stage_payload(&files, &root).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("my_project/src/lib.cairo")).unwrap(),
+            "mod contract;"
+        );
+        assert!(root.join("my_project/Scarb.toml").exists());
+    }
+
+    #[test]
+    fn test_check_artifact_matching_hash() {
+        let expected =
+            ClassHash::new("0x523e006fd59721c46bdb1955859fe3abb57201ef27cb6e483a079166f845c5c")
+                .unwrap();
+        let actual = check_artifact(Utf8Path::new(FIXTURE), &expected).unwrap();
+        assert!(actual.matches(&expected));
+    }
+
+    #[test]
+    fn test_check_artifact_mismatch() {
+        let expected = ClassHash::new("0x123").unwrap();
+        let error = check_artifact(Utf8Path::new(FIXTURE), &expected).unwrap_err();
+        assert_eq!(error.error_code(), "E037");
+        assert!(format!("{error}").contains("expected 0x123"));
+    }
+}
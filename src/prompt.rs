@@ -0,0 +1,234 @@
+//! Interactive prompts and the detection of environments where nobody can
+//! answer them.
+//!
+//! Every choice the tool may ask the user for goes through [`Prompter`]. When
+//! running non-interactively, because `--non-interactive` was given, a CI
+//! environment was detected or stdin isn't a terminal, a choice either falls
+//! back to a deterministic default or fails with the argument that makes it.
+
+use dialoguer::Select;
+use std::{env, fmt, io::IsTerminal};
+use thiserror::Error;
+
+/// Environment variables set by common CI providers
+const CI_VARIABLES: [&str; 9] = [
+    "CI",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "CIRCLECI",
+    "TRAVIS",
+    "BUILDKITE",
+    "JENKINS_URL",
+    "TF_BUILD",
+    "TEAMCITY_VERSION",
+];
+
+#[derive(Debug, Error)]
+pub enum PromptError {
+    #[error("[E027] Interactive prompt failed: {error}\n\nSuggestions:\n  • Use {argument} to skip the prompt\n  • Ensure terminal supports interactive input\n  • Check that stdin is available\n  • Use --non-interactive to fail instead of prompting")]
+    Failed {
+        argument: String,
+        error: dialoguer::Error,
+    },
+
+    #[error("[E051] A choice is required, but prompting is disabled ({reason}): {prompt}\n\nSuggestions:\n  • Use {argument} to choose\n  • Available options:\n{}", options.iter().map(|option| format!("    - {option}")).collect::<Vec<_>>().join("\n"))]
+    NonInteractive {
+        prompt: String,
+        reason: NonInteractiveReason,
+        argument: String,
+        options: Vec<String>,
+    },
+}
+
+impl PromptError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::Failed { .. } => "E027",
+            Self::NonInteractive { .. } => "E051",
+        }
+    }
+}
+
+/// Why prompting is disabled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonInteractiveReason {
+    /// `--non-interactive` was given
+    Flag,
+    /// A CI environment was detected through the variable
+    Ci(&'static str),
+    /// stdin isn't a terminal
+    NotATerminal,
+}
+
+impl fmt::Display for NonInteractiveReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flag => write!(f, "--non-interactive"),
+            Self::Ci(variable) => write!(f, "CI detected through {variable}"),
+            Self::NotATerminal => write!(f, "stdin is not a terminal"),
+        }
+    }
+}
+
+/// Outcome of a choice when nobody can be asked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fallback {
+    /// Pick the option at this index
+    Default(usize),
+    /// Fail with [`PromptError::NonInteractive`]
+    Fail,
+}
+
+/// A choice between several options
+#[derive(Debug, Clone)]
+pub struct Choice<'a> {
+    /// Question shown to the user
+    pub prompt: &'a str,
+    pub options: Vec<String>,
+    /// Command line argument that makes the choice without prompting
+    pub argument: &'a str,
+    pub fallback: Fallback,
+}
+
+/// Single entry point for prompting the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompter {
+    non_interactive: Option<NonInteractiveReason>,
+}
+
+impl Prompter {
+    /// Prompter for the current environment, non-interactive when
+    /// `non_interactive` is set, a CI environment is detected or stdin isn't
+    /// a terminal.
+    #[must_use]
+    pub fn detect(non_interactive: bool) -> Self {
+        let reason = if non_interactive {
+            Some(NonInteractiveReason::Flag)
+        } else if let Some(variable) = detect_ci(|name| env::var(name).ok()) {
+            Some(NonInteractiveReason::Ci(variable))
+        } else if !std::io::stdin().is_terminal() {
+            Some(NonInteractiveReason::NotATerminal)
+        } else {
+            None
+        };
+        Self {
+            non_interactive: reason,
+        }
+    }
+
+    #[must_use]
+    pub const fn non_interactive(reason: NonInteractiveReason) -> Self {
+        Self {
+            non_interactive: Some(reason),
+        }
+    }
+
+    /// Why prompting is disabled, `None` when the user can be asked
+    #[must_use]
+    pub const fn non_interactive_reason(&self) -> Option<&NonInteractiveReason> {
+        self.non_interactive.as_ref()
+    }
+
+    /// Ask the user to pick one of `choice.options`, returning its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the prompt fails, or if running non-interactively
+    /// and the choice has no default.
+    pub fn select(&self, choice: &Choice<'_>) -> Result<usize, PromptError> {
+        match (&self.non_interactive, choice.fallback) {
+            (None, fallback) => {
+                let mut select = Select::new()
+                    .with_prompt(choice.prompt)
+                    .items(&choice.options);
+                if let Fallback::Default(index) = fallback {
+                    select = select.default(index);
+                }
+                select.interact().map_err(|error| PromptError::Failed {
+                    argument: choice.argument.to_string(),
+                    error,
+                })
+            }
+            (Some(_), Fallback::Default(index)) => Ok(index),
+            (Some(reason), Fallback::Fail) => Err(PromptError::NonInteractive {
+                prompt: choice.prompt.to_string(),
+                reason: reason.clone(),
+                argument: choice.argument.to_string(),
+                options: choice.options.clone(),
+            }),
+        }
+    }
+}
+
+/// Name of the variable revealing a CI environment, if any. `CI=false` and
+/// `CI=0` are honoured as opting out.
+fn detect_ci(var: impl Fn(&str) -> Option<String>) -> Option<&'static str> {
+    CI_VARIABLES.into_iter().find(|name| {
+        var(name).is_some_and(|value| {
+            let value = value.trim();
+            !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false")
+        })
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn choice(fallback: Fallback) -> Choice<'static> {
+        Choice {
+            prompt: "Which contract?",
+            options: vec!["Token".to_string(), "Vault".to_string()],
+            argument: "--contract-name <NAME>",
+            fallback,
+        }
+    }
+
+    #[test]
+    fn test_detect_ci() {
+        let env = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect();
+            detect_ci(move |name| vars.get(name).cloned())
+        };
+        assert_eq!(env(&[]), None);
+        assert_eq!(env(&[("CI", "true")]), Some("CI"));
+        assert_eq!(env(&[("CI", "false")]), None);
+        assert_eq!(env(&[("CI", "0")]), None);
+        assert_eq!(env(&[("GITHUB_ACTIONS", "true")]), Some("GITHUB_ACTIONS"));
+        assert_eq!(
+            env(&[("JENKINS_URL", "https://ci.example.com")]),
+            Some("JENKINS_URL")
+        );
+    }
+
+    #[test]
+    fn test_non_interactive_uses_default() {
+        let prompter = Prompter::non_interactive(NonInteractiveReason::Ci("CI"));
+        assert_eq!(prompter.select(&choice(Fallback::Default(1))).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_non_interactive_without_default_fails() {
+        let prompter = Prompter::non_interactive(NonInteractiveReason::Flag);
+        let error = prompter.select(&choice(Fallback::Fail)).unwrap_err();
+        assert_eq!(error.error_code(), "E051");
+
+        let message = error.to_string();
+        assert!(message.contains("--non-interactive"));
+        assert!(message.contains("--contract-name <NAME>"));
+        assert!(message.contains("    - Vault"));
+    }
+
+    #[test]
+    fn test_detect_flag_takes_precedence() {
+        assert_eq!(
+            Prompter::detect(true).non_interactive_reason(),
+            Some(&NonInteractiveReason::Flag)
+        );
+    }
+}
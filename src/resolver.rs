@@ -1,18 +1,22 @@
 use camino::{Utf8Path, Utf8PathBuf};
+use globset::{GlobBuilder, GlobMatcher};
 use itertools::Itertools;
+use lazy_static::lazy_static;
 use log::debug;
+use regex::Regex;
 use scarb_metadata::{Metadata, MetadataCommand, PackageMetadata};
 use serde::Deserialize;
 use std::{
-    collections::{HashMap, HashSet},
-    ffi::OsStr,
-    fs,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt, fs,
     path::PathBuf,
 };
 use thiserror::Error;
 use url::Url;
 use walkdir::WalkDir;
 
+use crate::voyager::{self, SourceGlobs};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("[E012] Invalid dependency path for '{name}': {path}\n\nSuggestions:\n  • Check that the path exists and is accessible\n  • Use relative paths from the current directory\n  • Verify the path format is correct\n  • Example: path:../my-dependency")]
@@ -32,6 +36,15 @@ pub enum Error {
 
     #[error("[E027] Module not found: '{module}' from '{parent_file}'\n\nSuggestions:\n  • Check that the module file exists\n  • Verify module name spelling\n  • Ensure proper file structure (module.rs or module/mod.rs)")]
     ModuleNotFound { module: String, parent_file: String },
+
+    #[error("[E043] Invalid glob '{pattern}': {error}\n\nSuggestions:\n  • Globs are matched against paths relative to the package root\n  • Use * for a single path segment and ** for any number of directories\n  • Example: --exclude 'src/fixtures/**'")]
+    InvalidGlob { pattern: String, error: String },
+
+    #[error("[E044] Invalid [tool.voyager] section in package '{package}': {error}\n\nSuggestions:\n  • include and exclude must be lists of globs\n  • Example: exclude = [\"src/fixtures/**\"]")]
+    ToolSection { package: String, error: String },
+
+    #[error("[E065] Workspace manifest '{path}' also defines package '{package}', which isn't submitted\n\nSuggestions:\n  • Move package '{package}' from the workspace root into a member directory\n  • Verify package '{package}' itself or a package depending on it\n  • Stop inheriting from the workspace in the verified package and drop --lock-file, so that the workspace manifest isn't needed")]
+    RootPackageLeftOut { path: String, package: String },
 }
 
 impl Error {
@@ -43,6 +56,9 @@ impl Error {
             Self::TomlParseError { .. } => "E025",
             Self::IoError { .. } => "E026",
             Self::ModuleNotFound { .. } => "E027",
+            Self::InvalidGlob { .. } => "E043",
+            Self::ToolSection { .. } => "E044",
+            Self::RootPackageLeftOut { .. } => "E065",
         }
     }
 }
@@ -111,6 +127,125 @@ pub fn gather_packages(
     Ok(())
 }
 
+/// The package named `selected` followed by the packages it depends on
+/// through path dependencies, directly or transitively. Packages that
+/// aren't in `packages` are skipped.
+#[must_use]
+pub fn path_dependency_closure(
+    packages: &[PackageMetadata],
+    selected: &str,
+) -> Vec<PackageMetadata> {
+    let mut closure: Vec<PackageMetadata> = vec![];
+    let mut queue: VecDeque<&str> = VecDeque::from([selected]);
+    while let Some(name) = queue.pop_front() {
+        if closure.iter().any(|package| package.name == name) {
+            continue;
+        }
+        let Some(package) = packages.iter().find(|package| package.name == name) else {
+            continue;
+        };
+        queue.extend(
+            package
+                .dependencies
+                .iter()
+                .filter(|dependency| dependency.source.repr.starts_with("path"))
+                .map(|dependency| dependency.name.as_str()),
+        );
+        closure.push(package.clone());
+    }
+    closure
+}
+
+/// Whether the manifest of `package` inherits a field or a dependency from
+/// the `[workspace]` section, e.g. `version.workspace = true`.
+///
+/// # Errors
+///
+/// Will return `Err` if the manifest can't be read or parsed.
+pub fn inherits_from_workspace(package: &PackageMetadata) -> Result<bool, Error> {
+    fn inherits(value: &toml::Value) -> bool {
+        match value {
+            toml::Value::Table(table) => table.iter().any(|(key, value)| {
+                (key == "workspace" && value.as_bool() == Some(true)) || inherits(value)
+            }),
+            toml::Value::Array(values) => values.iter().any(inherits),
+            _ => false,
+        }
+    }
+
+    let path = &package.manifest_path;
+    let content = fs::read_to_string(path).map_err(|e| Error::IoError {
+        path: path.to_string(),
+        error: e.to_string(),
+    })?;
+    let manifest: toml::Table = toml::from_str(&content).map_err(|e| Error::TomlParseError {
+        path: path.to_string(),
+        error: e.to_string(),
+    })?;
+    // The package's own [workspace] section doesn't inherit anything
+    Ok(manifest
+        .iter()
+        .any(|(key, value)| key != "workspace" && inherits(value)))
+}
+
+/// Workspace manifest at `manifest_path` with its `members` narrowed down to
+/// `members`, so that building from the workspace root only loads the
+/// submitted packages. The root package, if any, is never listed.
+///
+/// # Errors
+///
+/// Will return `Err` if the manifest can't be read or parsed, or if it
+/// defines a root package that isn't among `members`.
+pub fn narrowed_workspace_manifest(
+    manifest_path: &Utf8Path,
+    members: &[&PackageMetadata],
+) -> Result<String, Error> {
+    let content = fs::read_to_string(manifest_path).map_err(|e| Error::IoError {
+        path: manifest_path.to_string(),
+        error: e.to_string(),
+    })?;
+    let mut manifest: toml::Table =
+        toml::from_str(&content).map_err(|e| Error::TomlParseError {
+            path: manifest_path.to_string(),
+            error: e.to_string(),
+        })?;
+
+    if let Some(package) = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+    {
+        if !members
+            .iter()
+            .any(|member| member.manifest_path == manifest_path)
+        {
+            return Err(Error::RootPackageLeftOut {
+                path: manifest_path.to_string(),
+                package: package.as_str().unwrap_or_default().to_string(),
+            });
+        }
+    }
+
+    let root = manifest_path.parent().unwrap_or(manifest_path);
+    let paths = members
+        .iter()
+        .filter(|member| member.manifest_path != manifest_path)
+        .filter_map(|member| member.root.strip_prefix(root).ok())
+        .map(|path| toml::Value::String(path.as_str().replace('\\', "/")))
+        .sorted_by(|a, b| a.as_str().cmp(&b.as_str()))
+        .collect();
+    let workspace = manifest
+        .entry("workspace")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    if let Some(workspace) = workspace.as_table_mut() {
+        workspace.insert("members".to_string(), toml::Value::Array(paths));
+    }
+
+    toml::to_string(&manifest).map_err(|e| Error::TomlParseError {
+        path: manifest_path.to_string(),
+        error: e.to_string(),
+    })
+}
+
 /// # Errors
 ///
 /// Will return `Err` if it can't read files from the directory that
@@ -119,6 +254,238 @@ pub fn package_sources(package_metadata: &PackageMetadata) -> Result<Vec<Utf8Pat
     package_sources_with_test_files(package_metadata, false)
 }
 
+/// Compile a glob the way `--include`/`--exclude` and `[tool.voyager]`
+/// globs are matched: `*` stays within a path segment, `**` spans several.
+///
+/// # Errors
+///
+/// Will return `Err` if `pattern` isn't a valid glob.
+pub fn compile_glob(pattern: &str) -> Result<GlobMatcher, Error> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| Error::InvalidGlob {
+            pattern: pattern.to_string(),
+            error: e.kind().to_string(),
+        })
+}
+
+/// Include and exclude globs of a single package, compiled
+struct SourceFilter {
+    root: Utf8PathBuf,
+    include: Vec<(String, GlobMatcher)>,
+    exclude: Vec<(String, GlobMatcher)>,
+}
+
+impl SourceFilter {
+    fn new(root: &Utf8Path, globs: &SourceGlobs) -> Result<Self, Error> {
+        let compile = |patterns: &[String]| -> Result<Vec<(String, GlobMatcher)>, Error> {
+            patterns
+                .iter()
+                .map(|pattern| Ok((pattern.clone(), compile_glob(pattern)?)))
+                .collect()
+        };
+        Ok(Self {
+            root: root.to_path_buf(),
+            include: compile(&globs.include)?,
+            exclude: compile(&globs.exclude)?,
+        })
+    }
+
+    fn matching<'a>(
+        &self,
+        globs: &'a [(String, GlobMatcher)],
+        path: &Utf8Path,
+    ) -> Option<&'a String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        globs
+            .iter()
+            .find(|(_, matcher)| matcher.is_match(relative))
+            .map(|(pattern, _)| pattern)
+    }
+
+    /// Override the built-in decisions: exclude globs win over everything
+    /// but the package manifest, include globs win over the built-in rules.
+    fn apply(
+        &self,
+        decisions: &mut Vec<FileDecision>,
+        manifest_path: &Utf8Path,
+    ) -> Result<(), Error> {
+        if self.include.is_empty() && self.exclude.is_empty() {
+            return Ok(());
+        }
+
+        // Procedural macro packages only list the files they looked at, add
+        // the other files matched by an include glob so the loop below
+        // can include them
+        if !self.include.is_empty() {
+            let known: HashSet<Utf8PathBuf> = decisions.iter().map(|d| d.path.clone()).collect();
+            for entry in WalkDir::new(&self.root)
+                .into_iter()
+                .filter_map(std::result::Result::ok)
+                .filter(|f| f.file_type().is_file())
+            {
+                let path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
+                if !known.contains(&path) && self.matching(&self.include, &path).is_some() {
+                    decisions.push(FileDecision::exclude(path, Reason::NotCairo));
+                }
+            }
+        }
+
+        for decision in decisions.iter_mut() {
+            if decision.path == manifest_path {
+                continue;
+            }
+            if let Some(pattern) = self.matching(&self.exclude, &decision.path) {
+                debug!("Excluding {} matched by glob {pattern}", decision.path);
+                decision.included = false;
+                decision.reason = Reason::ExcludeGlob {
+                    pattern: pattern.clone(),
+                };
+            } else if let Some(pattern) = self
+                .matching(&self.include, &decision.path)
+                .filter(|_| !decision.included)
+            {
+                debug!("Including {} matched by glob {pattern}", decision.path);
+                decision.included = true;
+                decision.reason = Reason::IncludeGlob {
+                    pattern: pattern.clone(),
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a candidate file was included in or excluded from the payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    CairoSource,
+    ScarbManifest,
+    ManifestReadme,
+    ManifestLicenseFile,
+    /// Test file inside `src/`, only kept with `--test-files`
+    TestInSrc {
+        pattern: &'static str,
+    },
+    /// Inside a test, example or benchmark directory
+    ExcludedDirectory {
+        pattern: &'static str,
+    },
+    NotCairo,
+    RustOutsideProcMacro,
+    CargoManifestOutsideProcMacro,
+    ProcMacroCargoManifest,
+    ProcMacroCargoLock,
+    ProcMacroEntryPoint,
+    /// Rust module declared by `parent`, relative to the package root
+    ReachableModule {
+        parent: Utf8PathBuf,
+        module: String,
+    },
+    MacroAttribute {
+        attribute: &'static str,
+    },
+    RustTestOrExample,
+    UnusedRustFile,
+    WorkspaceManifest,
+    /// Workspace manifest when no selected package inherits from it
+    UnusedWorkspaceManifest,
+    /// `Scarb.lock`, only kept with `--lock-file`
+    LockFile,
+    IncludeGlob {
+        pattern: String,
+    },
+    ExcludeGlob {
+        pattern: String,
+    },
+    /// Source file of a package target, e.g. `src/lib.cairo`
+    CrateRoot {
+        target: String,
+    },
+    /// Cairo file no `mod` declaration leads to
+    UnreachableModule,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CairoSource => write!(f, "cairo source"),
+            Self::ScarbManifest => write!(f, "Scarb manifest"),
+            Self::ManifestReadme => write!(f, "manifest readme"),
+            Self::ManifestLicenseFile => write!(f, "manifest license-file"),
+            Self::TestInSrc { pattern } => write!(f, "matched {pattern} in src"),
+            Self::ExcludedDirectory { pattern } => write!(f, "matched {pattern}"),
+            Self::NotCairo => write!(f, "not .cairo"),
+            Self::RustOutsideProcMacro => {
+                write!(f, "Rust file outside a procedural macro package")
+            }
+            Self::CargoManifestOutsideProcMacro => {
+                write!(f, "Cargo.toml outside a procedural macro package")
+            }
+            Self::ProcMacroCargoManifest => write!(f, "procedural macro Cargo.toml"),
+            Self::ProcMacroCargoLock => write!(f, "procedural macro Cargo.lock"),
+            Self::ProcMacroEntryPoint => write!(f, "procedural macro entry point"),
+            Self::ReachableModule { parent, module } => {
+                write!(f, "reachable from {parent} via mod {module}")
+            }
+            Self::MacroAttribute { attribute } => write!(f, "contains {attribute}"),
+            Self::RustTestOrExample => write!(f, "test, example or binary file"),
+            Self::UnusedRustFile => {
+                write!(f, "not reachable from lib.rs and no macro attribute")
+            }
+            Self::WorkspaceManifest => write!(f, "workspace manifest, members narrowed down"),
+            Self::UnusedWorkspaceManifest => {
+                write!(f, "workspace manifest, not inherited from")
+            }
+            Self::LockFile => write!(f, "Scarb.lock, requires --lock-file"),
+            Self::IncludeGlob { pattern } => write!(f, "matched include glob {pattern}"),
+            Self::ExcludeGlob { pattern } => write!(f, "matched exclude glob {pattern}"),
+            Self::CrateRoot { target } => write!(f, "crate root of {target} target"),
+            Self::UnreachableModule => write!(f, "not reachable from the crate root"),
+        }
+    }
+}
+
+/// Decision taken for a single candidate file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDecision {
+    pub path: Utf8PathBuf,
+    pub included: bool,
+    pub reason: Reason,
+}
+
+impl FileDecision {
+    #[must_use]
+    pub const fn include(path: Utf8PathBuf, reason: Reason) -> Self {
+        Self {
+            path,
+            included: true,
+            reason,
+        }
+    }
+
+    #[must_use]
+    pub const fn exclude(path: Utf8PathBuf, reason: Reason) -> Self {
+        Self {
+            path,
+            included: false,
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for FileDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.included {
+            write!(f, "{}", self.reason)
+        } else {
+            write!(f, "excluded: {}", self.reason)
+        }
+    }
+}
+
 /// # Errors
 ///
 /// Will return `Err` if it can't read files from the directory that
@@ -127,6 +494,48 @@ pub fn package_sources_with_test_files(
     package_metadata: &PackageMetadata,
     include_test_files: bool,
 ) -> Result<Vec<Utf8PathBuf>, Error> {
+    Ok(explain_package_sources(
+        package_metadata,
+        include_test_files,
+        &SourceGlobs::default(),
+    )?
+    .into_iter()
+    .filter(|decision| decision.included)
+    .map(|decision| decision.path)
+    .collect())
+}
+
+/// Walk every candidate file of a package and decide whether it belongs in
+/// the verification payload, recording the reason for each decision.
+///
+/// `globs` are applied on top of the `include`/`exclude` globs from the
+/// package's `[tool.voyager]` section.
+///
+/// # Errors
+///
+/// Will return `Err` if it can't read files from the directory that
+/// metadata points to or if a glob is invalid.
+pub fn explain_package_sources(
+    package_metadata: &PackageMetadata,
+    include_test_files: bool,
+    globs: &SourceGlobs,
+) -> Result<Vec<FileDecision>, Error> {
+    let package_globs =
+        voyager::source_globs(package_metadata).map_err(|e| Error::ToolSection {
+            package: package_metadata.name.clone(),
+            error: e.to_string(),
+        })?;
+    let filter = SourceFilter::new(&package_metadata.root, &package_globs.merged(globs))?;
+
+    let mut decisions = classify_package_files(package_metadata, include_test_files)?;
+    filter.apply(&mut decisions, &package_metadata.manifest_path)?;
+    Ok(decisions)
+}
+
+fn classify_package_files(
+    package_metadata: &PackageMetadata,
+    include_test_files: bool,
+) -> Result<Vec<FileDecision>, Error> {
     debug!("Collecting sources for package: {}", package_metadata.name);
     debug!("Package root: {}", package_metadata.root);
     debug!("Package manifest: {}", package_metadata.manifest_path);
@@ -149,68 +558,57 @@ pub fn package_sources_with_test_files(
         }
     }
 
-    let mut sources: Vec<Utf8PathBuf> = WalkDir::new(package_metadata.root.clone())
+    let mut decisions: Vec<FileDecision> = WalkDir::new(package_metadata.root.clone())
         .into_iter()
         .filter_map(std::result::Result::ok)
         .filter(|f| f.file_type().is_file())
-        .filter(|f| {
-            // Check if this is a test file
-            if let Some(path_str) = f.path().to_str() {
-                // Check if the path contains test directories but only if it's in src/
-                let is_in_src = path_str.contains("/src/");
-                let has_test_in_path = path_str.contains("/test") || path_str.contains("/tests/");
-
-                if is_in_src && has_test_in_path {
-                    // This is a test file in src/
-                    return include_test_files;
-                }
+        .map(|f| -> Result<FileDecision, Error> {
+            let path = Utf8PathBuf::try_from(f.path().to_path_buf())?;
+            Ok(classify_source(path, include_test_files))
+        })
+        .try_collect()?;
 
-                // Exclude test directories outside src/
-                if path_str.contains("/tests/")
-                    || path_str.contains("/test/")
-                    || path_str.contains("/examples/")
-                    || path_str.contains("/benchmarks/")
-                {
-                    return false;
-                }
+    // Only keep the Cairo files the compiler would actually load
+    let roots = cairo_crate_roots(package_metadata);
+    if roots.is_empty() {
+        debug!(
+            "No crate root found for package {}, keeping every Cairo file",
+            package_metadata.name
+        );
+    } else {
+        let reachable = cairo_module_graph(&roots, &package_metadata.root)?;
+        for decision in &mut decisions {
+            let is_test = matches!(decision.reason, Reason::TestInSrc { .. });
+            if !(decision.included || is_test) || decision.path.extension() != Some(CAIRO_EXT) {
+                continue;
             }
-
-            // Include Cairo files
-            if let Some(ext) = f.path().extension() {
-                if ext == OsStr::new(CAIRO_EXT) {
-                    return true;
+            match reachable.get(&decision.path) {
+                // Loaded outside of tests, whatever its path looks like
+                Some(module) if !module.test_only => {
+                    decision.included = true;
+                    decision.reason = module.reason.clone();
                 }
-                // Only include Rust files if the package has been validated as a procedural macro
-                if ext == OsStr::new("rs") {
-                    // This will be handled by the procedural macro collection logic above
-                    // if this package is a valid procedural macro package
-                    return false;
+                // Keep the test reason, it's what --test-files controls
+                Some(_) if is_test => {}
+                Some(module) => decision.reason = module.reason.clone(),
+                None if decision.included => {
+                    decision.included = false;
+                    decision.reason = Reason::UnreachableModule;
                 }
+                None => {}
             }
-
-            // Include Scarb.toml files
-            if f.file_name() == OsStr::new("Scarb.toml") {
-                return true;
-            }
-
-            // Only include Cargo.toml if this package is a validated procedural macro
-            // (handled by the specialized collection above) or if there's no Cargo.toml
-            // indicating this is a pure Cairo package
-            if f.file_name() == OsStr::new("Cargo.toml") {
-                // Don't include Cargo.toml files from non-procedural macro packages
-                // They will be included by the specialized collection if they are valid proc macros
-                return false;
-            }
-
-            false
-        })
-        .map(walkdir::DirEntry::into_path)
-        .map(Utf8PathBuf::try_from)
-        .try_collect()?;
+        }
+    }
 
     // Ensure the package's own manifest is included
-    if !sources.contains(&package_metadata.manifest_path) {
-        sources.push(package_metadata.manifest_path.clone());
+    if !decisions
+        .iter()
+        .any(|d| d.included && d.path == package_metadata.manifest_path)
+    {
+        decisions.push(FileDecision::include(
+            package_metadata.manifest_path.clone(),
+            Reason::ScarbManifest,
+        ));
     }
 
     let package_root = &package_metadata.root;
@@ -222,7 +620,11 @@ pub fn package_sources_with_test_files(
         .map(Utf8Path::new)
         .map(Utf8Path::to_path_buf)
     {
-        sources.push(package_root.join(lic));
+        include_from_manifest(
+            &mut decisions,
+            package_root.join(lic),
+            Reason::ManifestLicenseFile,
+        );
     }
 
     if let Some(readme) = package_metadata
@@ -232,10 +634,310 @@ pub fn package_sources_with_test_files(
         .map(Utf8Path::new)
         .map(Utf8Path::to_path_buf)
     {
-        sources.push(package_root.join(readme));
+        include_from_manifest(
+            &mut decisions,
+            package_root.join(readme),
+            Reason::ManifestReadme,
+        );
+    }
+
+    Ok(decisions)
+}
+
+/// Source files of the non-test targets of a package, with the target kind.
+fn cairo_crate_roots(package_metadata: &PackageMetadata) -> Vec<(Utf8PathBuf, Reason)> {
+    let mut roots: Vec<(Utf8PathBuf, Reason)> = vec![];
+    for target in &package_metadata.targets {
+        if target.kind == "test"
+            || !target.source_path.exists()
+            || roots.iter().any(|(path, _)| *path == target.source_path)
+        {
+            continue;
+        }
+        roots.push((
+            target.source_path.clone(),
+            Reason::CrateRoot {
+                target: target.kind.clone(),
+            },
+        ));
+    }
+    roots
+}
+
+/// Cairo file reachable from a crate root
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ReachableFile {
+    reason: Reason,
+    /// Only reached through `#[cfg(test)]` modules
+    test_only: bool,
+}
+
+/// Follow `mod x;` declarations from every crate root and return each
+/// reachable Cairo file with the reason it was reached.
+///
+/// # Errors
+/// Returns an error if a reachable file cannot be read
+fn cairo_module_graph(
+    roots: &[(Utf8PathBuf, Reason)],
+    package_root: &Utf8Path,
+) -> Result<HashMap<Utf8PathBuf, ReachableFile>, Error> {
+    let mut reachable: HashMap<Utf8PathBuf, ReachableFile> = roots
+        .iter()
+        .map(|(path, reason)| {
+            let file = ReachableFile {
+                reason: reason.clone(),
+                test_only: false,
+            };
+            (path.clone(), file)
+        })
+        .collect();
+    // Files still to scan, with the directory their submodules live in and
+    // whether they were only reached through test modules
+    let mut queue: VecDeque<(Utf8PathBuf, Utf8PathBuf, bool)> = roots
+        .iter()
+        .map(|(path, _)| {
+            let dir = path.parent().unwrap_or(package_root).to_path_buf();
+            (path.clone(), dir, false)
+        })
+        .collect();
+
+    while let Some((file_path, module_dir, test_only)) = queue.pop_front() {
+        let content = fs::read_to_string(&file_path).map_err(|e| Error::IoError {
+            path: file_path.to_string(),
+            error: e.to_string(),
+        })?;
+
+        for module in parse_cairo_module_declarations(&content) {
+            let mut module_file = module_dir.clone();
+            for parent in &module.inline_parents {
+                module_file.push(parent);
+            }
+            module_file.push(format!("{}.{CAIRO_EXT}", module.name));
+            let test_only = test_only || module.cfg_test;
+
+            match reachable.get(&module_file) {
+                // Reached again outside of tests, so are its submodules
+                Some(file) if file.test_only && !test_only => {}
+                Some(_) => continue,
+                None => {}
+            }
+            if !module_file.exists() {
+                // Might be generated by a plugin or behind a cfg we don't evaluate
+                debug!(
+                    "Module file not found: {} declared in {file_path}",
+                    module.name
+                );
+                continue;
+            }
+
+            debug!("Adding module dependency: {} -> {module_file}", module.name);
+            let parent = file_path
+                .strip_prefix(package_root)
+                .unwrap_or(&file_path)
+                .to_path_buf();
+            reachable.insert(
+                module_file.clone(),
+                ReachableFile {
+                    reason: Reason::ReachableModule {
+                        parent,
+                        module: module.path(),
+                    },
+                    test_only,
+                },
+            );
+            // Submodules of `dir/x.cairo` live in `dir/x/`
+            let submodule_dir = module_file.with_extension("");
+            queue.push_back((module_file, submodule_dir, test_only));
+        }
+    }
+
+    Ok(reachable)
+}
+
+/// Module declared in a Cairo file
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CairoModule {
+    pub name: String,
+    /// Inline modules the declaration is nested in, outermost first
+    pub inline_parents: Vec<String>,
+    /// Attributes on the declaration, e.g. `#[starknet::contract]`
+    pub attributes: Vec<String>,
+    /// Whether the body follows inline, `mod x { ... }`, rather than in
+    /// its own file
+    pub inline: bool,
+    /// Whether the declaration, or an inline module it is nested in, is
+    /// behind `#[cfg(test)]`
+    pub cfg_test: bool,
+}
+
+impl CairoModule {
+    pub fn path(&self) -> String {
+        self.inline_parents
+            .iter()
+            .chain(std::iter::once(&self.name))
+            .join("::")
+    }
+}
+
+/// Parse Cairo source code to find `mod x;` declarations, keeping track of
+/// the inline `mod x { ... }` blocks they are nested in.
+fn parse_cairo_module_declarations(content: &str) -> Vec<CairoModule> {
+    parse_cairo_modules(content)
+        .into_iter()
+        .filter(|module| !module.inline)
+        .collect()
+}
+
+/// Parse Cairo source code to find every module declaration, both `mod x;`
+/// and inline `mod x { ... }`, with the attributes preceding it.
+pub(crate) fn parse_cairo_modules(content: &str) -> Vec<CairoModule> {
+    lazy_static! {
+        static ref MOD_DECLARATION: Regex = Regex::new(
+            r"^((?:#\[[^\]]*\]\s*)*)(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*([;{])"
+        )
+        .expect("module declaration regex is valid");
+        static ref ATTRIBUTE: Regex =
+            Regex::new(r"#\[[^\]]*\]").expect("attribute regex is valid");
+    }
+
+    let mut modules = Vec::new();
+    let mut depth = 0usize;
+    // Open inline modules with the brace depth inside their body and
+    // whether they are behind `#[cfg(test)]`
+    let mut inline: Vec<(String, usize, bool)> = Vec::new();
+    // Attributes on the lines preceding the next item
+    let mut attributes: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let (code, braces) = scan_cairo_line(line);
+        let code = code.trim();
+
+        if let Some(captures) = MOD_DECLARATION.captures(code) {
+            let name = captures[2].to_string();
+            attributes.extend(
+                ATTRIBUTE
+                    .find_iter(&captures[1])
+                    .map(|attribute| attribute.as_str().to_string()),
+            );
+            let is_inline = &captures[3] == "{";
+            let cfg_test = inline.iter().any(|(_, _, cfg_test)| *cfg_test)
+                || attributes.iter().any(|attribute| is_cfg_test(attribute));
+            debug!("Found module declaration: {name}");
+            modules.push(CairoModule {
+                name: name.clone(),
+                inline_parents: inline.iter().map(|(name, _, _)| name.clone()).collect(),
+                attributes: std::mem::take(&mut attributes),
+                inline: is_inline,
+                cfg_test,
+            });
+            if is_inline {
+                inline.push((name, depth + 1, cfg_test));
+            }
+        } else if ATTRIBUTE.replace_all(code, "").trim().is_empty() {
+            attributes.extend(
+                ATTRIBUTE
+                    .find_iter(code)
+                    .map(|attribute| attribute.as_str().to_string()),
+            );
+        } else {
+            attributes.clear();
+        }
+
+        for brace in braces {
+            if brace == '{' {
+                depth += 1;
+            } else {
+                depth = depth.saturating_sub(1);
+                while inline.last().is_some_and(|(_, body, _)| *body > depth) {
+                    inline.pop();
+                }
+            }
+        }
+    }
+
+    modules
+}
+
+/// Cut the `//` comment off a line of Cairo code and collect its braces,
+/// skipping both inside string literals, `"..."` and `'...'`.
+fn scan_cairo_line(line: &str) -> (&str, Vec<char>) {
+    let mut braces = Vec::new();
+    // Quote of the string literal being scanned
+    let mut quote: Option<char> = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '/') if chars.peek().is_some_and(|(_, next)| *next == '/') => {
+                return (&line[..index], braces);
+            }
+            (None, '{' | '}') => braces.push(c),
+            (None, _) => {}
+        }
+    }
+    (line, braces)
+}
+
+/// Whether `attribute` is `#[cfg(test)]`, whatever its spacing.
+fn is_cfg_test(attribute: &str) -> bool {
+    attribute
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .eq("#[cfg(test)]".chars())
+}
+
+/// Decide on a file found while walking a regular Cairo package.
+fn classify_source(path: Utf8PathBuf, include_test_files: bool) -> FileDecision {
+    let path_str = path.as_str();
+
+    // Check if the path contains test directories but only if it's in src/
+    let is_in_src = path_str.contains("/src/");
+    let has_test_in_path = path_str.contains("/test") || path_str.contains("/tests/");
+
+    if is_in_src && has_test_in_path {
+        // This is a test file in src/
+        let reason = Reason::TestInSrc { pattern: "/test" };
+        return if include_test_files {
+            FileDecision::include(path, reason)
+        } else {
+            FileDecision::exclude(path, reason)
+        };
+    }
+
+    // Exclude test directories outside src/
+    if let Some(pattern) = ["/tests/", "/test/", "/examples/", "/benchmarks/"]
+        .into_iter()
+        .find(|pattern| path_str.contains(pattern))
+    {
+        return FileDecision::exclude(path, Reason::ExcludedDirectory { pattern });
+    }
+
+    match path.extension() {
+        // Include Cairo files
+        Some(CAIRO_EXT) => return FileDecision::include(path, Reason::CairoSource),
+        // Rust files are only collected for validated procedural macro packages
+        Some("rs") => return FileDecision::exclude(path, Reason::RustOutsideProcMacro),
+        _ => {}
     }
 
-    Ok(sources)
+    match path.file_name() {
+        Some("Scarb.toml") => FileDecision::include(path, Reason::ScarbManifest),
+        // Cargo.toml is only collected for validated procedural macro packages
+        Some("Cargo.toml") => FileDecision::exclude(path, Reason::CargoManifestOutsideProcMacro),
+        _ => FileDecision::exclude(path, Reason::NotCairo),
+    }
+}
+
+/// Include a file referenced from the manifest, replacing an earlier
+/// exclusion of the same file.
+fn include_from_manifest(decisions: &mut Vec<FileDecision>, path: Utf8PathBuf, reason: Reason) {
+    decisions.retain(|d| d.included || d.path != path);
+    decisions.push(FileDecision::include(path, reason));
 }
 
 pub fn biggest_common_prefix<P: AsRef<Utf8Path> + Clone>(
@@ -354,27 +1056,33 @@ fn validate_cargo_toml_for_proc_macro(cargo_toml_path: &Utf8Path) -> Result<bool
 fn collect_procedural_macro_rust_files(
     package_metadata: &PackageMetadata,
     include_test_files: bool,
-) -> Result<Vec<Utf8PathBuf>, Error> {
+) -> Result<Vec<FileDecision>, Error> {
     debug!(
         "Collecting procedural macro files for package: {}",
         package_metadata.name
     );
 
-    let mut required_files = HashSet::new();
+    let mut decisions = BTreeMap::new();
     let package_root = &package_metadata.root;
 
     // Always include Cargo.toml for procedural macros
     let cargo_toml_path = package_root.join("Cargo.toml");
     if cargo_toml_path.exists() {
         debug!("Adding Cargo.toml: {cargo_toml_path}");
-        required_files.insert(cargo_toml_path);
+        include_file(
+            &mut decisions,
+            cargo_toml_path,
+            Reason::ProcMacroCargoManifest,
+        );
     }
 
     // Always include Scarb.toml (already handled by package_sources_with_test_files)
     // but we need to ensure it's in our list
-    if !required_files.contains(&package_metadata.manifest_path) {
-        required_files.insert(package_metadata.manifest_path.clone());
-    }
+    include_file(
+        &mut decisions,
+        package_metadata.manifest_path.clone(),
+        Reason::ScarbManifest,
+    );
 
     // Include README and license files referenced in manifest
     if let Some(readme) = package_metadata
@@ -385,7 +1093,7 @@ fn collect_procedural_macro_rust_files(
     {
         if readme.exists() {
             debug!("Adding README: {readme}");
-            required_files.insert(readme);
+            include_file(&mut decisions, readme, Reason::ManifestReadme);
         }
     }
 
@@ -397,7 +1105,7 @@ fn collect_procedural_macro_rust_files(
     {
         if license.exists() {
             debug!("Adding license file: {license}");
-            required_files.insert(license);
+            include_file(&mut decisions, license, Reason::ManifestLicenseFile);
         }
     }
 
@@ -405,42 +1113,60 @@ fn collect_procedural_macro_rust_files(
     let lib_rs_path = package_root.join("src/lib.rs");
     if lib_rs_path.exists() {
         debug!("Starting dependency analysis from lib.rs: {lib_rs_path}");
-        required_files.insert(lib_rs_path.clone());
+        include_file(
+            &mut decisions,
+            lib_rs_path.clone(),
+            Reason::ProcMacroEntryPoint,
+        );
 
         // Recursively collect module dependencies
-        collect_rust_module_dependencies(&lib_rs_path, package_root, &mut required_files)?;
+        collect_rust_module_dependencies(&lib_rs_path, package_root, &mut decisions)?;
     }
 
     // Find files with procedural macro attributes
-    collect_macro_implementation_files(package_root, &mut required_files)?;
+    collect_macro_implementation_files(package_root, &mut decisions)?;
 
     // Include Cargo.lock if it exists (important for reproducible builds)
     let cargo_lock_path = package_root.join("Cargo.lock");
     if cargo_lock_path.exists() {
         debug!("Adding Cargo.lock: {cargo_lock_path}");
-        required_files.insert(cargo_lock_path);
+        include_file(&mut decisions, cargo_lock_path, Reason::ProcMacroCargoLock);
     }
 
-    // Convert to Vec and filter based on include_test_files
-    let mut sources: Vec<Utf8PathBuf> = required_files.into_iter().collect();
-
     // Filter test files if not requested
     if !include_test_files {
-        sources.retain(|path| !should_exclude_rust_file(path));
+        for decision in decisions.values_mut() {
+            if decision.included && should_exclude_rust_file(&decision.path) {
+                *decision = FileDecision::exclude(decision.path.clone(), Reason::RustTestOrExample);
+            }
+        }
     }
 
-    // Sort for consistent output
-    sources.sort();
+    // BTreeMap keeps the output sorted for consistency
+    let decisions: Vec<FileDecision> = decisions.into_values().collect();
 
     debug!(
         "Collected {} files for procedural macro package",
-        sources.len()
+        decisions.iter().filter(|d| d.included).count()
     );
-    for source in &sources {
-        debug!("  - {source}");
+    for decision in &decisions {
+        debug!("  - {} ({decision})", decision.path);
     }
 
-    Ok(sources)
+    Ok(decisions)
+}
+
+/// Record `path` as included unless it already is, replacing an earlier
+/// exclusion.
+fn include_file(
+    decisions: &mut BTreeMap<Utf8PathBuf, FileDecision>,
+    path: Utf8PathBuf,
+    reason: Reason,
+) {
+    if decisions.get(&path).is_some_and(|d| d.included) {
+        return;
+    }
+    decisions.insert(path.clone(), FileDecision::include(path, reason));
 }
 
 /// Recursively collect Rust module dependencies from a source file
@@ -450,7 +1176,7 @@ fn collect_procedural_macro_rust_files(
 fn collect_rust_module_dependencies(
     file_path: &Utf8Path,
     package_root: &Utf8Path,
-    required_files: &mut HashSet<Utf8PathBuf>,
+    decisions: &mut BTreeMap<Utf8PathBuf, FileDecision>,
 ) -> Result<(), Error> {
     debug!("Analyzing module dependencies in: {file_path}");
 
@@ -470,12 +1196,23 @@ fn collect_rust_module_dependencies(
     for module_name in module_declarations {
         match resolve_module_file_path(file_path, &module_name, package_root) {
             Ok(module_file) => {
-                if module_file.exists() && !required_files.contains(&module_file) {
+                if module_file.exists() && !decisions.contains_key(&module_file) {
                     debug!("Adding module dependency: {module_name} -> {module_file}");
-                    required_files.insert(module_file.clone());
+                    let parent = file_path
+                        .strip_prefix(package_root)
+                        .unwrap_or(file_path)
+                        .to_path_buf();
+                    include_file(
+                        decisions,
+                        module_file.clone(),
+                        Reason::ReachableModule {
+                            parent,
+                            module: module_name,
+                        },
+                    );
 
                     // Recursively collect dependencies of this module
-                    collect_rust_module_dependencies(&module_file, package_root, required_files)?;
+                    collect_rust_module_dependencies(&module_file, package_root, decisions)?;
                 }
             }
             Err(Error::ModuleNotFound { .. }) => {
@@ -605,7 +1342,7 @@ fn resolve_module_file_path(
 /// Returns an error if files cannot be read or processed
 fn collect_macro_implementation_files(
     package_root: &Utf8Path,
-    required_files: &mut HashSet<Utf8PathBuf>,
+    decisions: &mut BTreeMap<Utf8PathBuf, FileDecision>,
 ) -> Result<(), Error> {
     debug!("Searching for procedural macro implementation files in: {package_root}");
 
@@ -625,18 +1362,31 @@ fn collect_macro_implementation_files(
                 let rust_file_path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
 
                 // Skip files we've already processed
-                if required_files.contains(&rust_file_path) {
+                if decisions.contains_key(&rust_file_path) {
                     continue;
                 }
 
                 // Skip test files and examples
                 if should_exclude_rust_file(&rust_file_path) {
+                    decisions.insert(
+                        rust_file_path.clone(),
+                        FileDecision::exclude(rust_file_path, Reason::RustTestOrExample),
+                    );
                     continue;
                 }
 
-                if contains_procedural_macro_attributes(&rust_file_path)? {
+                if let Some(attribute) = find_procedural_macro_attribute(&rust_file_path)? {
                     debug!("Found procedural macro implementation in: {rust_file_path}");
-                    required_files.insert(rust_file_path);
+                    include_file(
+                        decisions,
+                        rust_file_path,
+                        Reason::MacroAttribute { attribute },
+                    );
+                } else {
+                    decisions.insert(
+                        rust_file_path.clone(),
+                        FileDecision::exclude(rust_file_path, Reason::UnusedRustFile),
+                    );
                 }
             }
         }
@@ -645,11 +1395,11 @@ fn collect_macro_implementation_files(
     Ok(())
 }
 
-/// Check if a Rust file contains procedural macro attributes
+/// Find the first procedural macro attribute used in a Rust file
 ///
 /// # Errors
 /// Returns an error if the file cannot be read
-fn contains_procedural_macro_attributes(file_path: &Utf8Path) -> Result<bool, Error> {
+fn find_procedural_macro_attribute(file_path: &Utf8Path) -> Result<Option<&'static str>, Error> {
     let content = fs::read_to_string(file_path).map_err(|e| Error::IoError {
         path: file_path.to_string(),
         error: e.to_string(),
@@ -663,14 +1413,14 @@ fn contains_procedural_macro_attributes(file_path: &Utf8Path) -> Result<bool, Er
         "#[post_process]",
     ];
 
-    for attr in &macro_attributes {
+    for attr in macro_attributes {
         if content.contains(attr) {
             debug!("Found procedural macro attribute {attr} in {file_path}");
-            return Ok(true);
+            return Ok(Some(attr));
         }
     }
 
-    Ok(false)
+    Ok(None)
 }
 
 /// Check if a Rust file should be excluded (tests, examples, etc.)
@@ -981,8 +1731,8 @@ pub fn my_attribute(_attr: TokenStream, item: TokenStream) -> TokenStream {
         let macro_file_path = temp_path.join("macro_impl.rs");
         std::fs::write(&macro_file_path, rust_with_macro).unwrap();
 
-        let has_macros = contains_procedural_macro_attributes(&macro_file_path).unwrap();
-        assert!(has_macros);
+        let attribute = find_procedural_macro_attribute(&macro_file_path).unwrap();
+        assert_eq!(attribute, Some("#[inline_macro]"));
 
         // Create Rust file without procedural macro attributes
         let rust_without_macro = r#"
@@ -998,8 +1748,8 @@ struct MyStruct {
         let normal_file_path = temp_path.join("normal.rs");
         std::fs::write(&normal_file_path, rust_without_macro).unwrap();
 
-        let has_no_macros = contains_procedural_macro_attributes(&normal_file_path).unwrap();
-        assert!(!has_no_macros);
+        let attribute = find_procedural_macro_attribute(&normal_file_path).unwrap();
+        assert_eq!(attribute, None);
     }
 
     #[test]
@@ -1026,4 +1776,502 @@ struct MyStruct {
             "/src/utils.rs"
         )));
     }
+
+    #[test]
+    fn test_classify_source_reasons() {
+        let explain = |path: &str, include_test_files: bool| {
+            classify_source(Utf8PathBuf::from(path), include_test_files).to_string()
+        };
+
+        assert_eq!(explain("/p/src/lib.cairo", false), "cairo source");
+        assert_eq!(explain("/p/Scarb.toml", false), "Scarb manifest");
+        assert_eq!(
+            explain("/p/src/tests/foo.cairo", false),
+            "excluded: matched /test in src"
+        );
+        assert_eq!(
+            explain("/p/src/tests/foo.cairo", true),
+            "matched /test in src"
+        );
+        assert_eq!(
+            explain("/p/examples/foo.cairo", true),
+            "excluded: matched /examples/"
+        );
+        assert_eq!(explain("/p/notes.txt", false), "excluded: not .cairo");
+        assert_eq!(
+            explain("/p/build.rs", false),
+            "excluded: Rust file outside a procedural macro package"
+        );
+        assert_eq!(
+            explain("/p/Cargo.toml", false),
+            "excluded: Cargo.toml outside a procedural macro package"
+        );
+    }
+
+    #[test]
+    fn test_manifest_file_replaces_exclusion() {
+        let readme = Utf8PathBuf::from("/p/README.md");
+        let mut decisions = vec![classify_source(readme.clone(), false)];
+        assert!(!decisions[0].included);
+
+        include_from_manifest(&mut decisions, readme.clone(), Reason::ManifestReadme);
+        assert_eq!(
+            decisions,
+            vec![FileDecision::include(readme, Reason::ManifestReadme)]
+        );
+        assert_eq!(decisions[0].to_string(), "manifest readme");
+    }
+
+    #[test]
+    fn test_rust_module_reachability_reasons() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).unwrap();
+        std::fs::create_dir_all(root.join("src/foo")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "mod foo;\nmod missing;\n").unwrap();
+        std::fs::write(root.join("src/foo/mod.rs"), "pub mod bar;\n").unwrap();
+        std::fs::write(root.join("src/foo/bar.rs"), "").unwrap();
+        std::fs::write(root.join("src/unused.rs"), "").unwrap();
+
+        let lib_rs = root.join("src/lib.rs");
+        let mut decisions = BTreeMap::new();
+        include_file(&mut decisions, lib_rs.clone(), Reason::ProcMacroEntryPoint);
+        collect_rust_module_dependencies(&lib_rs, &root, &mut decisions).unwrap();
+        collect_macro_implementation_files(&root, &mut decisions).unwrap();
+
+        let reasons: Vec<(String, String)> = decisions
+            .values()
+            .map(|d| {
+                (
+                    d.path.strip_prefix(&root).unwrap().to_string(),
+                    d.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (
+                    "src/foo/bar.rs".to_string(),
+                    "reachable from src/foo/mod.rs via mod bar".to_string()
+                ),
+                (
+                    "src/foo/mod.rs".to_string(),
+                    "reachable from src/lib.rs via mod foo".to_string()
+                ),
+                (
+                    "src/lib.rs".to_string(),
+                    "procedural macro entry point".to_string()
+                ),
+                (
+                    "src/unused.rs".to_string(),
+                    "excluded: not reachable from lib.rs and no macro attribute".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_filter_globs() {
+        let root = Utf8PathBuf::from("/p");
+        let manifest = root.join("Scarb.toml");
+        let mut decisions: Vec<FileDecision> = [
+            "src/lib.cairo",
+            "src/testing_utils.cairo",
+            "src/fixtures/generated.cairo",
+            "Scarb.toml",
+        ]
+        .into_iter()
+        .map(|path| classify_source(root.join(path), false))
+        .collect();
+        assert!(!decisions[1].included);
+
+        let globs = SourceGlobs {
+            include: vec!["src/testing_utils.cairo".to_string()],
+            exclude: vec!["src/fixtures/**".to_string(), "*.toml".to_string()],
+        };
+        let filter = SourceFilter::new(&root, &globs).unwrap();
+        filter.apply(&mut decisions, &manifest).unwrap();
+
+        let explained: Vec<String> = decisions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            explained,
+            vec![
+                "cairo source",
+                "matched include glob src/testing_utils.cairo",
+                "excluded: matched exclude glob src/fixtures/**",
+                "Scarb manifest",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_glob() {
+        let glob = compile_glob("src/*.cairo").unwrap();
+        assert!(glob.is_match("src/lib.cairo"));
+        assert!(!glob.is_match("src/nested/lib.cairo"));
+        assert!(compile_glob("src/**/*.cairo")
+            .unwrap()
+            .is_match("src/nested/lib.cairo"));
+
+        let error = compile_glob("src/[.cairo").unwrap_err();
+        assert_eq!(error.error_code(), "E043");
+    }
+
+    #[test]
+    fn test_cairo_module_declaration_parsing() {
+        let content = r"
+use starknet::ContractAddress;
+mod token;
+pub mod vault; // the vault
+pub(crate) mod utils;
+#[cfg(test)]
+mod tests;
+// mod commented;
+mod inner {
+    mod nested;
+    pub mod deeper {
+        mod leaf;
+    }
+    fn helper() {}
+}
+mod after;
+";
+        let modules: Vec<String> = parse_cairo_module_declarations(content)
+            .iter()
+            .map(CairoModule::path)
+            .collect();
+        assert_eq!(
+            modules,
+            vec![
+                "token",
+                "vault",
+                "utils",
+                "tests",
+                "inner::nested",
+                "inner::deeper::leaf",
+                "after",
+            ]
+        );
+
+        let modules = parse_cairo_module_declarations("#[cfg(test)] mod tests;");
+        assert_eq!(
+            modules,
+            vec![CairoModule {
+                name: "tests".to_string(),
+                inline_parents: vec![],
+                attributes: vec!["#[cfg(test)]".to_string()],
+                inline: false,
+                cfg_test: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cairo_module_parsing_skips_strings() {
+        let content = r#"
+mod outer {
+    fn url() -> ByteArray { "https://example.com" }
+    fn brace() -> felt252 { '}' }
+    fn quoted() -> ByteArray { "\"{" }
+    mod inside;
+}
+#[doc(url: "https://example.com")]
+mod after; // "{"
+"#;
+        let modules = parse_cairo_module_declarations(content);
+        let paths: Vec<String> = modules.iter().map(CairoModule::path).collect();
+        assert_eq!(paths, vec!["outer::inside", "after"]);
+        assert_eq!(
+            modules[1].attributes,
+            vec!["#[doc(url: \"https://example.com\")]"]
+        );
+
+        assert_eq!(
+            scan_cairo_line(r#"let url = "https://a.io/{id}"; // {"#),
+            (r#"let url = "https://a.io/{id}"; "#, vec![])
+        );
+        assert_eq!(
+            scan_cairo_line("fn f() { 'a}' } // }"),
+            ("fn f() { 'a}' } ", vec!['{', '}'])
+        );
+    }
+
+    #[test]
+    fn test_cairo_module_attributes() {
+        let content = r#"
+#[starknet::interface]
+trait IToken<T> {}
+
+#[starknet::contract]
+#[feature("deprecated_legacy_map")]
+pub mod Token {
+    #[storage]
+    struct Storage {}
+}
+
+#[cfg(test)]
+fn helper() {}
+mod plain;
+"#;
+        let modules = parse_cairo_modules(content);
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].name, "Token");
+        assert!(modules[0].inline);
+        assert_eq!(
+            modules[0].attributes,
+            vec![
+                "#[starknet::contract]",
+                "#[feature(\"deprecated_legacy_map\")]"
+            ]
+        );
+        assert_eq!(modules[1].name, "plain");
+        assert!(!modules[1].inline);
+        assert!(modules[1].attributes.is_empty());
+    }
+
+    #[test]
+    fn test_cairo_module_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).unwrap();
+        std::fs::create_dir_all(root.join("src/foo")).unwrap();
+        std::fs::create_dir_all(root.join("src/inner")).unwrap();
+        std::fs::write(
+            root.join("src/lib.cairo"),
+            "mod foo;\nmod inner {\n    mod bar;\n}\nmod generated;\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/foo.cairo"), "pub mod baz;\n").unwrap();
+        std::fs::write(root.join("src/foo/baz.cairo"), "mod foo;\n").unwrap();
+        std::fs::write(root.join("src/inner/bar.cairo"), "").unwrap();
+        std::fs::write(root.join("src/scratch.cairo"), "").unwrap();
+
+        let roots = vec![(
+            root.join("src/lib.cairo"),
+            Reason::CrateRoot {
+                target: "lib".to_string(),
+            },
+        )];
+        let reachable = cairo_module_graph(&roots, &root).unwrap();
+
+        let mut explained: Vec<(String, String)> = reachable
+            .iter()
+            .map(|(path, file)| {
+                (
+                    path.strip_prefix(&root).unwrap().to_string(),
+                    file.reason.to_string(),
+                )
+            })
+            .collect();
+        explained.sort();
+        assert_eq!(
+            explained,
+            vec![
+                (
+                    "src/foo.cairo".to_string(),
+                    "reachable from src/lib.cairo via mod foo".to_string()
+                ),
+                (
+                    "src/foo/baz.cairo".to_string(),
+                    "reachable from src/foo.cairo via mod baz".to_string()
+                ),
+                (
+                    "src/inner/bar.cairo".to_string(),
+                    "reachable from src/lib.cairo via mod inner::bar".to_string()
+                ),
+                (
+                    "src/lib.cairo".to_string(),
+                    "crate root of lib target".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reachable_module_overrides_test_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("Scarb.toml"), "[package]\nname = \"app\"\n").unwrap();
+        std::fs::write(
+            root.join("src/lib.cairo"),
+            "mod testing_utils;\n#[cfg(test)]\nmod tests;\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/testing_utils.cairo"), "").unwrap();
+        std::fs::write(root.join("src/tests.cairo"), "").unwrap();
+        std::fs::write(root.join("src/test_scratch.cairo"), "").unwrap();
+        let package: PackageMetadata = serde_json::from_value(serde_json::json!({
+            "id": format!("app 0.1.0 (path+file://{root}/Scarb.toml)"),
+            "name": "app",
+            "version": "0.1.0",
+            "source": format!("path+file://{root}/Scarb.toml"),
+            "manifest_path": root.join("Scarb.toml"),
+            "root": root,
+            "dependencies": [],
+            "targets": [{
+                "kind": "lib",
+                "name": "app",
+                "source_path": root.join("src/lib.cairo"),
+                "params": {}
+            }],
+        }))
+        .unwrap();
+
+        let explain = |include_test_files: bool| {
+            let mut explained: Vec<(String, String)> =
+                classify_package_files(&package, include_test_files)
+                    .unwrap()
+                    .iter()
+                    .map(|decision| {
+                        (
+                            decision.path.strip_prefix(&root).unwrap().to_string(),
+                            decision.to_string(),
+                        )
+                    })
+                    .collect();
+            explained.sort();
+            explained
+        };
+        let testing_utils = (
+            "src/testing_utils.cairo".to_string(),
+            "reachable from src/lib.cairo via mod testing_utils".to_string(),
+        );
+
+        let explained = explain(false);
+        assert!(explained.contains(&testing_utils));
+        assert!(explained.contains(&(
+            "src/tests.cairo".to_string(),
+            "excluded: matched /test in src".to_string()
+        )));
+        let explained = explain(true);
+        assert!(explained.contains(&testing_utils));
+        assert!(explained.contains(&(
+            "src/tests.cairo".to_string(),
+            "matched /test in src".to_string()
+        )));
+        assert!(explained.contains(&(
+            "src/test_scratch.cairo".to_string(),
+            "excluded: not reachable from the crate root".to_string()
+        )));
+    }
+
+    fn package(root: &Utf8Path, name: &str, path_dependencies: &[&str]) -> PackageMetadata {
+        let manifest = root.join(name).join("Scarb.toml");
+        let dependencies: Vec<serde_json::Value> = path_dependencies
+            .iter()
+            .map(|dependency| {
+                serde_json::json!({
+                    "name": dependency,
+                    "version_req": "*",
+                    "source": format!("path+file://{root}/{dependency}/Scarb.toml"),
+                })
+            })
+            .chain(std::iter::once(serde_json::json!({
+                "name": "starknet",
+                "version_req": "^2.11.0",
+                "source": "registry+https://scarbs.xyz/",
+            })))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "id": format!("{name} 0.1.0 (path+file://{manifest})"),
+            "name": name,
+            "version": "0.1.0",
+            "source": format!("path+file://{manifest}"),
+            "manifest_path": manifest,
+            "root": root.join(name),
+            "dependencies": dependencies,
+            "targets": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_path_dependency_closure() {
+        let root = Utf8Path::new("/workspace");
+        let packages = vec![
+            package(root, "token", &["shared"]),
+            package(root, "vault", &["token", "math"]),
+            package(root, "shared", &[]),
+            package(root, "math", &["shared"]),
+            package(root, "unrelated", &[]),
+        ];
+
+        let names = |selected| {
+            path_dependency_closure(&packages, selected)
+                .into_iter()
+                .map(|package| package.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("vault"), vec!["vault", "token", "math", "shared"]);
+        assert_eq!(names("token"), vec!["token", "shared"]);
+        assert_eq!(names("unrelated"), vec!["unrelated"]);
+        assert!(names("missing").is_empty());
+    }
+
+    #[test]
+    fn test_inherits_from_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).unwrap();
+        let write_manifest = |name: &str, content: &str| {
+            std::fs::create_dir_all(root.join(name)).unwrap();
+            std::fs::write(root.join(name).join("Scarb.toml"), content).unwrap();
+            package(&root, name, &[])
+        };
+
+        let standalone = write_manifest(
+            "standalone",
+            "[package]\nname = \"standalone\"\nversion = \"0.1.0\"\n\n[workspace]\nmembers = []\n\n[dependencies]\nstarknet = \"2.11.0\"\n",
+        );
+        assert!(!inherits_from_workspace(&standalone).unwrap());
+
+        let version = write_manifest(
+            "version",
+            "[package]\nname = \"version\"\nversion.workspace = true\n",
+        );
+        assert!(inherits_from_workspace(&version).unwrap());
+
+        let dependency = write_manifest(
+            "dependency",
+            "[package]\nname = \"dependency\"\nversion = \"0.1.0\"\n\n[dependencies]\nstarknet.workspace = true\n",
+        );
+        assert!(inherits_from_workspace(&dependency).unwrap());
+    }
+
+    #[test]
+    fn test_narrowed_workspace_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).unwrap();
+        let manifest = root.join("Scarb.toml");
+        std::fs::write(
+            &manifest,
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let crates = root.join("crates");
+        let (token, shared) = (
+            package(&crates, "token", &["shared"]),
+            package(&crates, "shared", &[]),
+        );
+
+        let narrowed: toml::Table =
+            toml::from_str(&narrowed_workspace_manifest(&manifest, &[&token, &shared]).unwrap())
+                .unwrap();
+        assert_eq!(
+            narrowed["workspace"]["members"],
+            toml::Value::Array(vec!["crates/shared".into(), "crates/token".into()])
+        );
+        assert_eq!(
+            narrowed["workspace"]["package"]["version"].as_str(),
+            Some("0.1.0")
+        );
+
+        // A root package can't be left out of the workspace it defines
+        std::fs::write(
+            &manifest,
+            "[package]\nname = \"root\"\nversion = \"0.1.0\"\n\n[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        let error = narrowed_workspace_manifest(&manifest, &[&token]).unwrap_err();
+        assert_eq!(error.error_code(), "E065");
+    }
 }
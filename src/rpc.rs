@@ -0,0 +1,374 @@
+//! Minimal blocking client for the Starknet JSON-RPC API.
+//!
+//! Only the few methods needed to look up what is actually declared on chain
+//! are implemented, independently of the explorer API: `starknet_chainId`,
+//! `starknet_getClass` and `starknet_getClassHashAt`. Requests are made
+//! against the `latest` block.
+
+use log::debug;
+use reqwest::blocking::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use url::Url;
+
+use crate::class_hash::{ClassHash, ContractClass};
+
+/// `CONTRACT_NOT_FOUND` error code of the Starknet JSON-RPC specification
+const CONTRACT_NOT_FOUND: i64 = 20;
+/// `CLASS_HASH_NOT_FOUND` error code of the Starknet JSON-RPC specification
+const CLASS_HASH_NOT_FOUND: i64 = 28;
+
+#[derive(Debug, Error)]
+pub enum RpcError {
+    #[error("[E054] Failed to reach the Starknet RPC endpoint {url}: {error}\n\nSuggestions:\n  • Check that the RPC URL is correct and the node is running\n  • Verify your internet connection\n  • Set another endpoint with --rpc-url or rpc-url in [networks.<name>]")]
+    Request { url: String, error: String },
+
+    #[error("[E055] {method} failed on {url}: {message} (code {code})\n\nSuggestions:\n  • Check that the RPC endpoint serves the selected network\n  • Verify the node supports the Starknet JSON-RPC specification v0.7 or later")]
+    Rpc {
+        url: String,
+        method: &'static str,
+        code: i64,
+        message: String,
+    },
+
+    #[error("[E056] Unexpected response to {method} from {url}: {error}\n\nSuggestions:\n  • Check that the URL points to a Starknet JSON-RPC endpoint\n  • Cairo 0 classes are not supported")]
+    Decode {
+        url: String,
+        method: &'static str,
+        error: String,
+    },
+}
+
+impl RpcError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::Request { .. } => "E054",
+            Self::Rpc { .. } => "E055",
+            Self::Decode { .. } => "E056",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    result: Option<Value>,
+    error: Option<ResponseError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+/// Class found on chain by [`RpcClient::get_declared_class`]
+#[derive(Debug)]
+pub enum DeclaredClass {
+    Sierra(Box<ContractClass>),
+    /// Cairo 0 class, which has no Sierra program
+    Legacy,
+}
+
+impl DeclaredClass {
+    /// The Sierra class, `None` for Cairo 0 classes
+    #[must_use]
+    pub fn sierra(&self) -> Option<&ContractClass> {
+        match self {
+            Self::Sierra(class) => Some(class),
+            Self::Legacy => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RpcClient {
+    url: Url,
+    client: Client,
+}
+
+impl RpcClient {
+    #[must_use]
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            client: Client::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Chain identifier, e.g. `0x534e5f5345504f4c4941` for `SN_SEPOLIA`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request fails or the node returns an error.
+    pub fn chain_id(&self) -> Result<String, RpcError> {
+        self.call("starknet_chainId", json!([]))
+    }
+
+    /// Class declared as `class_hash`, whatever its Cairo version, `None` if
+    /// it isn't declared.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request fails, the node returns an error or the
+    /// Sierra class can't be decoded.
+    pub fn get_declared_class(
+        &self,
+        class_hash: &ClassHash,
+    ) -> Result<Option<DeclaredClass>, RpcError> {
+        let Some(class) = self.get_raw_class(class_hash)? else {
+            return Ok(None);
+        };
+        if class.get("sierra_program").is_none() {
+            return Ok(Some(DeclaredClass::Legacy));
+        }
+        serde_json::from_value(class)
+            .map(|class| Some(DeclaredClass::Sierra(Box::new(class))))
+            .map_err(|e| self.decode_error("starknet_getClass", e))
+    }
+
+    /// Sierra class declared as `class_hash`, `None` if it isn't declared.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request fails, the node returns an error or the
+    /// class isn't a Sierra class.
+    pub fn get_class(&self, class_hash: &ClassHash) -> Result<Option<ContractClass>, RpcError> {
+        let Some(class) = self.get_raw_class(class_hash)? else {
+            return Ok(None);
+        };
+        serde_json::from_value(class)
+            .map(Some)
+            .map_err(|e| self.decode_error("starknet_getClass", e))
+    }
+
+    /// Hash of the class of the contract deployed at `address`, `None` if no
+    /// contract is deployed there.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request fails or the node returns an error.
+    pub fn get_class_hash_at(&self, address: &str) -> Result<Option<ClassHash>, RpcError> {
+        let method = "starknet_getClassHashAt";
+        let class_hash: Option<String> = not_found_as_none(
+            self.call(
+                method,
+                json!({"block_id": "latest", "contract_address": address}),
+            ),
+            CONTRACT_NOT_FOUND,
+        )?;
+        class_hash
+            .map(|hash| ClassHash::new(&hash).map_err(|e| self.decode_error(method, e)))
+            .transpose()
+    }
+
+    fn get_raw_class(&self, class_hash: &ClassHash) -> Result<Option<Value>, RpcError> {
+        not_found_as_none(
+            self.call(
+                "starknet_getClass",
+                json!({"block_id": "latest", "class_hash": class_hash.to_string()}),
+            ),
+            CLASS_HASH_NOT_FOUND,
+        )
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: Value,
+    ) -> Result<T, RpcError> {
+        debug!("RPC {method} {params} on {}", self.url);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: Response = self
+            .client
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .and_then(reqwest::blocking::Response::json)
+            .map_err(|e| RpcError::Request {
+                url: self.url.to_string(),
+                error: e.to_string(),
+            })?;
+
+        if let Some(error) = response.error {
+            return Err(RpcError::Rpc {
+                url: self.url.to_string(),
+                method,
+                code: error.code,
+                message: error.message,
+            });
+        }
+        serde_json::from_value(response.result.unwrap_or(Value::Null))
+            .map_err(|e| self.decode_error(method, e))
+    }
+
+    fn decode_error(&self, method: &'static str, error: impl ToString) -> RpcError {
+        RpcError::Decode {
+            url: self.url.to_string(),
+            method,
+            error: error.to_string(),
+        }
+    }
+}
+
+/// Turn the "not found" error `code` of a lookup into `None`
+fn not_found_as_none<T>(result: Result<T, RpcError>, code: i64) -> Result<Option<T>, RpcError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(RpcError::Rpc { code: actual, .. }) if actual == code => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Name of a chain from its identifier, `SN_MAIN` for `0x534e5f4d41494e`.
+#[must_use]
+pub fn chain_name(chain_id: &str) -> Option<String> {
+    let digits = chain_id.trim_start_matches("0x");
+    let digits = if digits.len() % 2 == 1 {
+        format!("0{digits}")
+    } else {
+        digits.to_string()
+    };
+    let bytes = (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let name = String::from_utf8(bytes).ok()?;
+    let name = name.trim_start_matches('\0');
+    (!name.is_empty() && name.chars().all(|c| c.is_ascii_graphic())).then(|| name.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    const CLASS_HASH: &str = "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18";
+
+    fn mock_method(server: &mut mockito::Server, method: &str, body: &Value) -> mockito::Mock {
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": method})))
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create()
+    }
+
+    fn client(server: &mockito::Server) -> RpcClient {
+        RpcClient::new(Url::parse(&server.url()).unwrap())
+    }
+
+    #[test]
+    fn test_chain_id() {
+        let mut server = mockito::Server::new();
+        let _mock = mock_method(
+            &mut server,
+            "starknet_chainId",
+            &json!({"jsonrpc": "2.0", "id": 1, "result": "0x534e5f5345504f4c4941"}),
+        );
+
+        let chain_id = client(&server).chain_id().unwrap();
+        assert_eq!(chain_name(&chain_id).as_deref(), Some("SN_SEPOLIA"));
+    }
+
+    #[test]
+    fn test_get_class() {
+        let mut server = mockito::Server::new();
+        let _mock = mock_method(
+            &mut server,
+            "starknet_getClass",
+            &json!({"jsonrpc": "2.0", "id": 1, "result": {
+                "sierra_program": ["0x1", "0x2"],
+                "contract_class_version": "0.1.0",
+                "entry_points_by_type": {
+                    "EXTERNAL": [{"selector": "0x3", "function_idx": 0}],
+                    "L1_HANDLER": [],
+                    "CONSTRUCTOR": []
+                },
+                "abi": "[]"
+            }}),
+        );
+
+        let class = client(&server)
+            .get_class(&ClassHash::new(CLASS_HASH).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(class.contract_class_version, "0.1.0");
+        assert_eq!(class.sierra_program.len(), 2);
+        assert_eq!(class.entry_points_by_type.external[0].selector, "0x3");
+    }
+
+    #[test]
+    fn test_get_declared_legacy_class() {
+        let mut server = mockito::Server::new();
+        let _mock = mock_method(
+            &mut server,
+            "starknet_getClass",
+            &json!({"jsonrpc": "2.0", "id": 1, "result": {
+                "program": "H4sIAAAAAAAA",
+                "entry_points_by_type": {"EXTERNAL": [], "L1_HANDLER": [], "CONSTRUCTOR": []},
+                "abi": []
+            }}),
+        );
+
+        let class = client(&server)
+            .get_declared_class(&ClassHash::new(CLASS_HASH).unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(matches!(class, DeclaredClass::Legacy));
+        assert!(class.sierra().is_none());
+    }
+
+    #[test]
+    fn test_not_found_is_none() {
+        let mut server = mockito::Server::new();
+        let not_found = |code: i64| json!({"jsonrpc": "2.0", "id": 1, "error": {"code": code, "message": "not found"}});
+        let _class = mock_method(&mut server, "starknet_getClass", &not_found(28));
+        let _contract = mock_method(&mut server, "starknet_getClassHashAt", &not_found(20));
+
+        let rpc = client(&server);
+        assert!(rpc
+            .get_declared_class(&ClassHash::new(CLASS_HASH).unwrap())
+            .unwrap()
+            .is_none());
+        assert_eq!(rpc.get_class_hash_at("0x123").unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_class_hash_at() {
+        let mut server = mockito::Server::new();
+        let _mock = mock_method(
+            &mut server,
+            "starknet_getClassHashAt",
+            &json!({"jsonrpc": "2.0", "id": 1, "result": CLASS_HASH}),
+        );
+
+        let class_hash = client(&server).get_class_hash_at("0x123").unwrap().unwrap();
+        assert_eq!(class_hash.to_string(), CLASS_HASH);
+    }
+
+    #[test]
+    fn test_rpc_error() {
+        let mut server = mockito::Server::new();
+        let _mock = mock_method(
+            &mut server,
+            "starknet_chainId",
+            &json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "Method not found"}}),
+        );
+
+        let error = client(&server).chain_id().unwrap_err();
+        assert_eq!(error.error_code(), "E055");
+        assert!(error.to_string().contains("Method not found"));
+    }
+}
@@ -0,0 +1,495 @@
+//! Toolchain versions of a verification.
+//!
+//! The verification service compiles the payload with the Cairo and Scarb
+//! versions submitted along with it, those of the local installation. The
+//! Sierra program of a declared class records the version of the compiler
+//! that produced it, so a submission made with another version can't
+//! reproduce the declared class hash. That version is compared with the one
+//! to be submitted before uploading, as are the versions pinned by the
+//! project in `.tool-versions` (asdf) and in the `cairo-version` field of its
+//! `Scarb.toml`.
+//!
+//! The submitted versions can be set explicitly, e.g. to verify from a
+//! machine with another toolchain than the one the class was declared with.
+//! Such overrides must then agree with the versions pinned by the project.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::debug;
+use semver::{Version, VersionReq};
+use std::{fmt, fs};
+use thiserror::Error;
+
+/// File of the asdf version manager pinning tool versions
+pub const TOOL_VERSIONS: &str = ".tool-versions";
+
+#[derive(Debug, Error)]
+pub enum ToolchainError {
+    #[error("[E060] The declared class was compiled with Cairo {declared}, but it would be verified with Cairo {local}\n\nSuggestions:\n{}  • Or submit the declared version with --cairo-version {declared}\n  • Check the compiler version with 'scarb --version'\n  • Use --allow-toolchain-mismatch to submit anyway", install_hint(declared, pinned.as_deref()))]
+    CompilerMismatch {
+        declared: Version,
+        local: Version,
+        /// Scarb version pinned in `.tool-versions`, if any
+        pinned: Option<Box<Pin<Version>>>,
+    },
+
+    #[error("[E061] {0}\n\nSuggestions:\n  • Check the version given with --cairo-version/--scarb-version or in the configuration\n  • Update the pin if the project moved to another toolchain\n  • Use --allow-toolchain-mismatch to submit anyway")]
+    OverrideMismatch(Mismatch),
+
+    #[error("[E064] {flag} builds with the installed {installed}, but {submitted} would be submitted\n\nSuggestions:\n  • Install the submitted toolchain, e.g. 'asdf install scarb {}', and drop --cairo-version/--scarb-version\n  • Run without {flag}, a local build with another compiler tells nothing about the submitted one", submitted.scarb)]
+    LocalBuildMismatch {
+        flag: &'static str,
+        installed: Box<Toolchain>,
+        submitted: Box<Toolchain>,
+    },
+}
+
+impl ToolchainError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::CompilerMismatch { .. } => "E060",
+            Self::OverrideMismatch(_) => "E061",
+            Self::LocalBuildMismatch { .. } => "E064",
+        }
+    }
+}
+
+/// How to get the compiler of the declared class. Scarb releases bundle the
+/// Cairo compiler of the same version.
+fn install_hint(declared: &Version, pinned: Option<&Pin<Version>>) -> String {
+    match pinned {
+        Some(pin) if pin.version == *declared => format!(
+            "  • {} pins scarb {declared}: run 'asdf install' in the project to use it\n",
+            pin.path
+        ),
+        Some(pin) => format!(
+            "  • Install Scarb {declared}, e.g. 'asdf install scarb {declared}'\n  • Update the scarb {} pin in {} to {declared}\n",
+            pin.version, pin.path
+        ),
+        None => format!("  • Install Scarb {declared}, e.g. 'asdf install scarb {declared}'\n"),
+    }
+}
+
+/// Versions submitted with a verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    pub cairo: Version,
+    pub scarb: Version,
+}
+
+impl Toolchain {
+    /// `self` with the versions set in `overrides`
+    #[must_use]
+    pub fn with_overrides(self, overrides: &Overrides) -> Self {
+        Self {
+            cairo: overrides.cairo.clone().unwrap_or(self.cairo),
+            scarb: overrides.scarb.clone().unwrap_or(self.scarb),
+        }
+    }
+}
+
+impl fmt::Display for Toolchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Scarb {} (Cairo {})", self.scarb, self.cairo)
+    }
+}
+
+/// Versions given explicitly instead of those of the local installation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overrides {
+    pub cairo: Option<Version>,
+    pub scarb: Option<Version>,
+}
+
+/// A version pinned by a project file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pin<T> {
+    pub path: Utf8PathBuf,
+    pub version: T,
+}
+
+/// Versions pinned by a project
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pins {
+    /// Scarb version from `.tool-versions`
+    pub scarb: Option<Pin<Version>>,
+    /// Requirement from the `cairo-version` field of `Scarb.toml`
+    pub cairo: Option<Pin<VersionReq>>,
+}
+
+impl Pins {
+    /// Pins of the package with manifest `manifest_path`. `.tool-versions` is
+    /// looked up from the package root upwards, as asdf does, and
+    /// `cairo-version` may be inherited from the workspace manifest.
+    /// Unreadable or invalid pins are ignored.
+    #[must_use]
+    pub fn read(manifest_path: &Utf8Path, workspace_manifest_path: &Utf8Path) -> Self {
+        let scarb = manifest_path
+            .parent()
+            .and_then(find_tool_versions)
+            .and_then(|path| {
+                let contents = fs::read_to_string(&path)
+                    .inspect_err(|e| debug!("Could not read {path}: {e}"))
+                    .ok()?;
+                let version = parse_tool_versions(&contents)?;
+                Some(Pin { path, version })
+            });
+        let cairo = read_cairo_version(manifest_path, workspace_manifest_path).map(|version| Pin {
+            path: manifest_path.to_path_buf(),
+            version,
+        });
+        Self { scarb, cairo }
+    }
+}
+
+/// Closest `.tool-versions` in `dir` or its ancestors
+fn find_tool_versions(dir: &Utf8Path) -> Option<Utf8PathBuf> {
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(TOOL_VERSIONS))
+        .find(|path| path.is_file())
+}
+
+/// Scarb version of a `.tool-versions` file, e.g. `2.11.4` for
+/// `scarb 2.11.4`. When several versions are listed the first one is used,
+/// and `system`, `latest` or nightly pins are ignored.
+#[must_use]
+pub fn parse_tool_versions(contents: &str) -> Option<Version> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some("scarb"), version) => version,
+                _ => None,
+            }
+        })
+        .and_then(|version| {
+            Version::parse(version)
+                .inspect_err(|e| debug!("Ignoring scarb pin '{version}' of {TOOL_VERSIONS}: {e}"))
+                .ok()
+        })
+}
+
+/// `cairo-version` of a package manifest, following `workspace = true` to the
+/// `[workspace.package]` section of the workspace manifest.
+fn read_cairo_version(
+    manifest_path: &Utf8Path,
+    workspace_manifest_path: &Utf8Path,
+) -> Option<VersionReq> {
+    let read = |path: &Utf8Path| -> Option<toml::Table> {
+        let contents = fs::read_to_string(path)
+            .inspect_err(|e| debug!("Could not read {path}: {e}"))
+            .ok()?;
+        toml::from_str(&contents)
+            .inspect_err(|e| debug!("Could not parse {path}: {e}"))
+            .ok()
+    };
+    let manifest = read(manifest_path)?;
+    let field = manifest.get("package")?.get("cairo-version")?;
+    let requirement = match field {
+        toml::Value::String(requirement) => requirement.clone(),
+        toml::Value::Table(table)
+            if table.get("workspace").and_then(toml::Value::as_bool) == Some(true) =>
+        {
+            read(workspace_manifest_path)?
+                .get("workspace")?
+                .get("package")?
+                .get("cairo-version")?
+                .as_str()?
+                .to_string()
+        }
+        _ => return None,
+    };
+    VersionReq::parse(&requirement)
+        .inspect_err(|e| debug!("Ignoring cairo-version '{requirement}' of {manifest_path}: {e}"))
+        .ok()
+}
+
+/// A pinned version the submitted toolchain doesn't match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// `.tool-versions` pins another Scarb version
+    Scarb {
+        pin: Pin<Version>,
+        local: Version,
+        /// Whether the version was given explicitly
+        overridden: bool,
+    },
+    /// The Cairo version doesn't satisfy `cairo-version`
+    Cairo {
+        pin: Pin<VersionReq>,
+        local: Version,
+        /// Whether the version was given explicitly
+        overridden: bool,
+    },
+}
+
+impl Mismatch {
+    /// Whether the mismatching version was given explicitly rather than
+    /// taken from the local installation
+    #[must_use]
+    pub const fn is_override(&self) -> bool {
+        match self {
+            Self::Scarb { overridden, .. } | Self::Cairo { overridden, .. } => *overridden,
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scarb {
+                pin,
+                local,
+                overridden: false,
+            } => write!(
+                f,
+                "{} pins scarb {}, but Scarb {local} is used; run 'asdf install' to switch",
+                pin.path, pin.version
+            ),
+            Self::Scarb {
+                pin,
+                local,
+                overridden: true,
+            } => write!(
+                f,
+                "{} pins scarb {}, but Scarb {local} is set to be submitted",
+                pin.path, pin.version
+            ),
+            Self::Cairo {
+                pin,
+                local,
+                overridden: false,
+            } => write!(
+                f,
+                "{} requires cairo-version {}, but Cairo {local} is used",
+                pin.path, pin.version
+            ),
+            Self::Cairo {
+                pin,
+                local,
+                overridden: true,
+            } => write!(
+                f,
+                "{} requires cairo-version {}, but Cairo {local} is set to be submitted",
+                pin.path, pin.version
+            ),
+        }
+    }
+}
+
+/// Pins of the project that `toolchain` doesn't match, `overrides` telling
+/// which of its versions were given explicitly
+#[must_use]
+pub fn check_pins(toolchain: &Toolchain, overrides: &Overrides, pins: &Pins) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+    if let Some(pin) = pins
+        .scarb
+        .as_ref()
+        .filter(|pin| pin.version != toolchain.scarb)
+    {
+        mismatches.push(Mismatch::Scarb {
+            pin: pin.clone(),
+            local: toolchain.scarb.clone(),
+            overridden: overrides.scarb.is_some(),
+        });
+    }
+    if let Some(pin) = pins
+        .cairo
+        .as_ref()
+        .filter(|pin| !pin.version.matches(&toolchain.cairo))
+    {
+        mismatches.push(Mismatch::Cairo {
+            pin: pin.clone(),
+            local: toolchain.cairo.clone(),
+            overridden: overrides.cairo.is_some(),
+        });
+    }
+    mismatches
+}
+
+/// Check that `toolchain` compiles with the compiler of the declared class,
+/// whose version is `declared`.
+///
+/// # Errors
+///
+/// Returns `Err` if the Cairo versions differ.
+pub fn check_declared(
+    toolchain: &Toolchain,
+    declared: &Version,
+    pins: &Pins,
+) -> Result<(), ToolchainError> {
+    if toolchain.cairo == *declared {
+        return Ok(());
+    }
+    Err(ToolchainError::CompilerMismatch {
+        declared: declared.clone(),
+        local: toolchain.cairo.clone(),
+        pinned: pins.scarb.clone().map(Box::new),
+    })
+}
+
+/// Check that a local build done for `flag`, which runs the `installed`
+/// toolchain, uses the versions that are submitted.
+///
+/// # Errors
+///
+/// Returns `Err` if `overrides` select other versions than `installed`.
+pub fn check_local_build(
+    installed: &Toolchain,
+    overrides: &Overrides,
+    flag: &'static str,
+) -> Result<(), ToolchainError> {
+    let submitted = installed.clone().with_overrides(overrides);
+    if submitted == *installed {
+        return Ok(());
+    }
+    Err(ToolchainError::LocalBuildMismatch {
+        flag,
+        installed: Box::new(installed.clone()),
+        submitted: Box::new(submitted),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn toolchain(version: &str) -> Toolchain {
+        Toolchain {
+            cairo: Version::parse(version).unwrap(),
+            scarb: Version::parse(version).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_parse_tool_versions() {
+        let contents = "# toolchain\nstarknet-foundry 0.38.0\nscarb 2.11.4 2.10.1 # CI\n";
+        assert_eq!(parse_tool_versions(contents), Some(Version::new(2, 11, 4)));
+        assert_eq!(parse_tool_versions("scarb latest\n"), None);
+        assert_eq!(parse_tool_versions("starknet-foundry 0.38.0\n"), None);
+    }
+
+    #[test]
+    fn test_read_pins() {
+        let dir = TempDir::new().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        fs::create_dir(root.join("token")).unwrap();
+        fs::write(root.join(TOOL_VERSIONS), "scarb 2.10.1\n").unwrap();
+        fs::write(
+            root.join("Scarb.toml"),
+            "[workspace]\nmembers = [\"token\"]\n\n[workspace.package]\ncairo-version = \"2.10.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("token/Scarb.toml"),
+            "[package]\nname = \"token\"\nversion = \"0.1.0\"\ncairo-version.workspace = true\n",
+        )
+        .unwrap();
+
+        let pins = Pins::read(&root.join("token/Scarb.toml"), &root.join("Scarb.toml"));
+        assert_eq!(pins.scarb.unwrap().version, Version::new(2, 10, 1));
+        assert_eq!(
+            pins.cairo.unwrap().version,
+            VersionReq::parse("2.10.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_pins() {
+        let pins = Pins {
+            scarb: Some(Pin {
+                path: Utf8PathBuf::from(TOOL_VERSIONS),
+                version: Version::new(2, 10, 1),
+            }),
+            cairo: Some(Pin {
+                path: Utf8PathBuf::from("Scarb.toml"),
+                version: VersionReq::parse("2.10.0").unwrap(),
+            }),
+        };
+        let none = Overrides::default();
+        assert!(check_pins(&toolchain("2.10.1"), &none, &pins).is_empty());
+
+        let mismatches = check_pins(&toolchain("3.0.0"), &none, &pins);
+        assert_eq!(mismatches.len(), 2);
+        assert!(!mismatches[0].is_override());
+        assert_eq!(
+            mismatches[1].to_string(),
+            "Scarb.toml requires cairo-version ^2.10.0, but Cairo 3.0.0 is used"
+        );
+    }
+
+    #[test]
+    fn test_overrides() {
+        let pins = Pins {
+            scarb: Some(Pin {
+                path: Utf8PathBuf::from(TOOL_VERSIONS),
+                version: Version::new(2, 10, 1),
+            }),
+            cairo: None,
+        };
+        let overrides = Overrides {
+            cairo: Some(Version::new(2, 10, 1)),
+            scarb: Some(Version::new(2, 10, 0)),
+        };
+        let submitted = toolchain("2.11.4").with_overrides(&overrides);
+        assert_eq!(submitted, toolchain("2.10.1").with_overrides(&overrides));
+        assert_eq!(submitted.cairo, Version::new(2, 10, 1));
+
+        let mismatches = check_pins(&submitted, &overrides, &pins);
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].is_override());
+
+        let error = ToolchainError::OverrideMismatch(mismatches[0].clone());
+        assert_eq!(error.error_code(), "E061");
+        assert!(error
+            .to_string()
+            .contains(".tool-versions pins scarb 2.10.1, but Scarb 2.10.0 is set to be submitted"));
+    }
+
+    #[test]
+    fn test_check_declared() {
+        let pins = Pins {
+            scarb: Some(Pin {
+                path: Utf8PathBuf::from(TOOL_VERSIONS),
+                version: Version::new(2, 11, 4),
+            }),
+            cairo: None,
+        };
+        let declared = Version::new(2, 11, 4);
+        assert!(check_declared(&toolchain("2.11.4"), &declared, &pins).is_ok());
+
+        let error = check_declared(&toolchain("2.12.0"), &declared, &pins).unwrap_err();
+        assert_eq!(error.error_code(), "E060");
+        let message = error.to_string();
+        assert!(message
+            .contains("compiled with Cairo 2.11.4, but it would be verified with Cairo 2.12.0"));
+        assert!(message.contains("pins scarb 2.11.4: run 'asdf install'"));
+    }
+
+    #[test]
+    fn test_check_local_build() {
+        let installed = toolchain("2.11.4");
+        assert!(check_local_build(&installed, &Overrides::default(), "--precheck").is_ok());
+
+        // Overriding with the installed versions changes nothing
+        let same = Overrides {
+            cairo: Some(Version::new(2, 11, 4)),
+            scarb: None,
+        };
+        assert!(check_local_build(&installed, &same, "--precheck").is_ok());
+
+        let older = Overrides {
+            cairo: Some(Version::new(2, 10, 1)),
+            scarb: Some(Version::new(2, 10, 1)),
+        };
+        let error = check_local_build(&installed, &older, "--diagnose").unwrap_err();
+        assert_eq!(error.error_code(), "E064");
+        assert!(error.to_string().starts_with(
+            "[E064] --diagnose builds with the installed Scarb 2.11.4 (Cairo 2.11.4), but Scarb 2.10.1 (Cairo 2.10.1) would be submitted"
+        ));
+    }
+}
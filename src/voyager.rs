@@ -1,22 +1,67 @@
 use camino::Utf8PathBuf;
-use scarb_metadata::{Metadata, PackageId};
+use scarb_metadata::{Metadata, PackageId, PackageMetadata};
 use serde::Deserialize;
 use std::{collections::HashMap, path::PathBuf};
 use thiserror::Error;
 
+use crate::class_hash::ClassHash;
+
 pub type ContractMap = HashMap<String, Voyager>;
 
-#[allow(dead_code)]
+/// Keys of `[tool.voyager]` that configure the tool instead of naming a
+/// contract
+const RESERVED_KEYS: [&str; 3] = ["include", "exclude", "defaults"];
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Voyager {
     pub path: PathBuf,
     pub address: Option<String>,
+    #[serde(default, rename = "class-hash", alias = "class_hash")]
+    pub class_hash: Option<ClassHash>,
+}
+
+/// Include and exclude globs, matched against paths relative to the
+/// package root
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct SourceGlobs {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl SourceGlobs {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Append the globs of `other` after those of `self`.
+    #[must_use]
+    pub fn merged(&self, other: &Self) -> Self {
+        Self {
+            include: self.include.iter().chain(&other.include).cloned().collect(),
+            exclude: self.exclude.iter().chain(&other.exclude).cloned().collect(),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Deserialization(#[from] serde_json::Error),
+
+    #[error("[E063] '[tool.voyager.{0}]' looks like a contract, but '{0}' is a reserved key\n\nSuggestions:\n  • The key names the contract in 'verify --all', so rename the contract module and its entry\n  • Or verify the contract on its own with --contract-name instead of listing it under [tool.voyager]\n  • Reserved keys of [tool.voyager]: include, exclude, defaults")]
+    ReservedKey(String),
+}
+
+impl Error {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::Deserialization(_) => "E999",
+            Self::ReservedKey(_) => "E063",
+        }
+    }
 }
 
 // Use this instead of metadata.runtime_manifest, because of:
@@ -45,14 +90,51 @@ pub fn tool_section(metadata: &Metadata) -> Result<HashMap<PackageId, ContractMa
         }
 
         if let Some(tool) = package.tool_metadata("voyager") {
-            let contracts =
-                serde_json::from_value::<ContractMap>(tool.clone()).map_err(Error::from)?;
-            voyager.insert(package.id.clone(), contracts);
+            voyager.insert(package.id.clone(), contracts(tool)?);
         }
     }
     Ok(voyager)
 }
 
+fn contracts(tool: &serde_json::Value) -> Result<ContractMap, Error> {
+    let mut section = tool.clone();
+    if let Some(table) = section.as_object_mut() {
+        for key in RESERVED_KEYS {
+            // A contract entry would otherwise be silently dropped
+            if table
+                .get(key)
+                .is_some_and(|value| value.get("path").is_some())
+            {
+                return Err(Error::ReservedKey(key.to_string()));
+            }
+            table.remove(key);
+        }
+    }
+    serde_json::from_value::<ContractMap>(section).map_err(Error::from)
+}
+
+/// Include and exclude globs from the `[tool.voyager]` section of `package`.
+///
+/// # Errors
+///
+/// Will return `Err` if `include` or `exclude` aren't lists of strings.
+pub fn source_globs(package: &PackageMetadata) -> Result<SourceGlobs, Error> {
+    package
+        .tool_metadata("voyager")
+        .map_or_else(|| Ok(SourceGlobs::default()), globs)
+}
+
+fn globs(tool: &serde_json::Value) -> Result<SourceGlobs, Error> {
+    let section: serde_json::Map<String, serde_json::Value> = tool
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| RESERVED_KEYS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    serde_json::from_value(serde_json::Value::Object(section)).map_err(Error::from)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -100,6 +182,7 @@ mod tests {
         let voyager = Voyager {
             path: PathBuf::from("/test/path"),
             address: Some("0x123".to_string()),
+            class_hash: None,
         };
         let cloned = voyager.clone();
         assert_eq!(voyager.path, cloned.path);
@@ -111,6 +194,7 @@ mod tests {
         let voyager = Voyager {
             path: PathBuf::from("/test/path"),
             address: Some("0x123".to_string()),
+            class_hash: None,
         };
         let debug_str = format!("{voyager:?}");
         assert!(debug_str.contains("/test/path"));
@@ -133,6 +217,7 @@ mod tests {
             Voyager {
                 path: PathBuf::from("/test/contract1.cairo"),
                 address: Some("0x123".to_string()),
+                class_hash: None,
             },
         );
         contract_map.insert(
@@ -140,6 +225,7 @@ mod tests {
             Voyager {
                 path: PathBuf::from("/test/contract2.cairo"),
                 address: None,
+                class_hash: None,
             },
         );
 
@@ -153,4 +239,85 @@ mod tests {
         let contract2 = contract_map.get("contract2").unwrap();
         assert_eq!(contract2.address, None);
     }
+
+    #[test]
+    fn test_contract_map_deserialization() {
+        let section = serde_json::json!({
+            "token": {
+                "path": "src/token.cairo",
+                "class-hash": "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18"
+            },
+            "vault": { "path": "src/vault.cairo", "address": "0x123" }
+        });
+
+        let contracts = serde_json::from_value::<ContractMap>(section).unwrap();
+        assert_eq!(contracts.len(), 2);
+
+        let token = contracts.get("token").unwrap();
+        assert_eq!(
+            token.class_hash.as_ref().map(ToString::to_string),
+            Some("0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18".to_string())
+        );
+
+        let vault = contracts.get("vault").unwrap();
+        assert_eq!(vault.class_hash, None);
+        assert_eq!(vault.address, Some("0x123".to_string()));
+    }
+
+    #[test]
+    fn test_contract_map_rejects_invalid_class_hash() {
+        let section = serde_json::json!({
+            "token": { "path": "src/token.cairo", "class-hash": "not-a-hash" }
+        });
+
+        assert!(serde_json::from_value::<ContractMap>(section).is_err());
+    }
+
+    #[test]
+    fn test_source_globs_next_to_contracts() {
+        let section = serde_json::json!({
+            "defaults": { "network": "mainnet" },
+            "include": ["src/testing_utils.cairo"],
+            "exclude": ["src/fixtures/**"],
+            "token": { "path": "src/token.cairo" }
+        });
+
+        let contracts = contracts(&section).unwrap();
+        assert_eq!(contracts.len(), 1);
+        assert!(contracts.contains_key("token"));
+
+        let globs = globs(&section).unwrap();
+        assert_eq!(globs.include, vec!["src/testing_utils.cairo".to_string()]);
+        assert_eq!(globs.exclude, vec!["src/fixtures/**".to_string()]);
+
+        let cli = SourceGlobs {
+            include: vec![],
+            exclude: vec!["src/scratch.cairo".to_string()],
+        };
+        assert_eq!(
+            globs.merged(&cli).exclude,
+            vec![
+                "src/fixtures/**".to_string(),
+                "src/scratch.cairo".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contract_named_like_reserved_key() {
+        let section = serde_json::json!({
+            "include": { "path": "src/include.cairo" },
+            "token": { "path": "src/token.cairo" }
+        });
+
+        let error = contracts(&section).unwrap_err();
+        assert_eq!(error.error_code(), "E063");
+        assert!(error.to_string().contains("'include' is a reserved key"));
+    }
+
+    #[test]
+    fn test_source_globs_must_be_lists() {
+        let section = serde_json::json!({ "exclude": "src/fixtures/**" });
+        assert!(globs(&section).is_err());
+    }
 }
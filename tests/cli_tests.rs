@@ -0,0 +1,722 @@
+#![allow(clippy::unwrap_used)]
+
+#[cfg(unix)]
+use serde_json::{json, Value};
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(unix)]
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const ARTIFACT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/hello_starknet.contract_class.json"
+);
+
+/// Run the `voyager` binary in an empty directory, away from any user configuration
+fn voyager(args: &[&str]) -> Output {
+    let dir = TempDir::new().unwrap();
+    Command::new(env!("CARGO_BIN_EXE_voyager"))
+        .args(args)
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("HOME", dir.path())
+        .env_remove("VOYAGER_NETWORK")
+        .env_remove("VOYAGER_FORMAT")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_exit_code_of_failed_job() {
+    let mut server = mockito::Server::new();
+    // Status 3 is Fail: the sources compiled to another class hash
+    server
+        .mock("GET", "/class-verify/job/job-1")
+        .with_body(
+            r#"{"job_id": "job-1", "status": 3, "status_description": "hash mismatch", "class_hash": "0x123"}"#,
+        )
+        .create();
+
+    let output = voyager(&["status", "--url", &server.url(), "--job", "job-1"]);
+    assert_eq!(output.status.code(), Some(6));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E005"));
+}
+
+#[test]
+fn test_exit_code_of_usage_error() {
+    let output = voyager(&["status", "--job", "job-1", "--timeout", "soon"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_hash_ignores_broken_configuration() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join(".voyager.toml"), "[defaults\nnetwork = ").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_voyager"))
+        .args(["hash", ARTIFACT])
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    // Commands taking defaults from it still report the broken file
+    let output = Command::new(env!("CARGO_BIN_EXE_voyager"))
+        .args(["status", "--network", "mainnet", "--job", "job-1"])
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_hash_of_artifact() {
+    let output = voyager(&["hash", ARTIFACT]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        "0x0523e006fd59721c46bdb1955859fe3abb57201ef27cb6e483a079166f845c5c"
+    );
+}
+
+#[test]
+fn test_hash_of_invalid_artifact() {
+    let output = voyager(&["hash", "missing.contract_class.json"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[E030]"));
+
+    // CASM and other JSON files aren't Sierra classes
+    let dir = TempDir::new().unwrap();
+    let artifact = dir.path().join("app.compiled_contract_class.json");
+    fs::write(&artifact, r#"{"bytecode": []}"#).unwrap();
+    let output = voyager(&["hash", &artifact.display().to_string()]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[E031]"));
+}
+
+/// `scarb metadata` entry of the package `name` rooted at `root`
+#[cfg(unix)]
+fn package_metadata(root: &Path, name: &str, contract: bool) -> Value {
+    let root = root.display().to_string();
+    let target = |kind: &str| json!({ "kind": kind, "name": name, "source_path": format!("{root}/src/lib.cairo"), "params": {} });
+    let mut targets = vec![target("lib")];
+    if contract {
+        targets.push(target("starknet-contract"));
+    }
+    json!({
+        "id": format!("{name} 0.1.0 (path+file://{root}/Scarb.toml)"),
+        "name": name,
+        "version": "0.1.0",
+        "edition": "2024_07",
+        "source": format!("path+file://{root}/Scarb.toml"),
+        "manifest_path": format!("{root}/Scarb.toml"),
+        "root": root,
+        "dependencies": [],
+        "targets": targets,
+        "manifest_metadata": {}
+    })
+}
+
+/// `scarb metadata` output of a workspace whose members are `packages`
+#[cfg(unix)]
+fn scarb_metadata(root: &Path, packages: Vec<Value>) -> Value {
+    let root = root.display().to_string();
+    json!({
+        "version": 1,
+        "app_exe": null,
+        "app_version_info": {
+            "version": "2.11.4",
+            "commit_info": null,
+            "cairo": { "version": "2.11.4", "commit_info": null }
+        },
+        "target_dir": format!("{root}/target"),
+        "runtime_manifest": format!("{root}/Scarb.toml"),
+        "workspace": {
+            "manifest_path": format!("{root}/Scarb.toml"),
+            "root": root,
+            "members": packages.iter().map(|package| package["id"].clone()).collect::<Vec<_>>()
+        },
+        "packages": packages,
+        "compilation_units": [],
+        "current_profile": "release",
+        "profiles": ["release", "dev"]
+    })
+}
+
+const CONTRACTS: &str = "#[starknet::contract]\nmod HelloStarknet {\n    #[storage]\n    struct Storage {}\n}\n\n#[starknet::contract]\nmod Counter {\n    #[storage]\n    struct Storage {}\n}\n";
+
+/// Single package project `hello` with two contracts, and the `scarb
+/// metadata` output describing it
+#[cfg(unix)]
+fn hello_project(dir: &Path) -> Value {
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Scarb.toml"),
+        "[package]\nname = \"hello\"\nversion = \"0.1.0\"\n\n[[target.starknet-contract]]\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/lib.cairo"), CONTRACTS).unwrap();
+    scarb_metadata(dir, vec![package_metadata(dir, "hello", true)])
+}
+
+/// Workspace of two unrelated members, `app` defining the contracts and
+/// `utils`, both inheriting their version from the workspace
+#[cfg(unix)]
+fn workspace_project(dir: &Path) -> Value {
+    fs::write(
+        dir.join("Scarb.toml"),
+        "[workspace]\nmembers = [\"app\", \"utils\"]\n\n[workspace.package]\nversion = \"0.1.0\"\n\n[workspace.dependencies]\nstarknet = \"2.11.4\"\n",
+    )
+    .unwrap();
+    for (name, manifest, source) in [
+        (
+            "app",
+            "[package]\nname = \"app\"\nversion.workspace = true\n\n[dependencies]\nstarknet.workspace = true\n\n[[target.starknet-contract]]\n",
+            CONTRACTS,
+        ),
+        (
+            "utils",
+            "[package]\nname = \"utils\"\nversion.workspace = true\n",
+            "pub fn double(x: felt252) -> felt252 {\n    x * 2\n}\n",
+        ),
+    ] {
+        fs::create_dir_all(dir.join(name).join("src")).unwrap();
+        fs::write(dir.join(name).join("Scarb.toml"), manifest).unwrap();
+        fs::write(dir.join(name).join("src/lib.cairo"), source).unwrap();
+    }
+    scarb_metadata(
+        dir,
+        vec![
+            package_metadata(&dir.join("app"), "app", true),
+            package_metadata(&dir.join("utils"), "utils", false),
+        ],
+    )
+}
+
+/// Write a `scarb` stand-in printing `metadata`, for commands that only
+/// read the project metadata
+#[cfg(unix)]
+fn stub_scarb(dir: &Path, metadata: &Value) -> String {
+    let output = dir.join("metadata.json");
+    fs::write(&output, serde_json::to_string(metadata).unwrap()).unwrap();
+    let scarb = dir.join("scarb");
+    fs::write(&scarb, format!("#!/bin/sh\ncat '{}'\n", output.display())).unwrap();
+    fs::set_permissions(&scarb, fs::Permissions::from_mode(0o755)).unwrap();
+    scarb.display().to_string()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_json_report() {
+    let project = TempDir::new().unwrap();
+    let tools = TempDir::new().unwrap();
+    let scarb = stub_scarb(tools.path(), &hello_project(project.path()));
+    let list = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_voyager"))
+            .args(["list", "--format", "json"])
+            .current_dir(project.path())
+            .env("XDG_CONFIG_HOME", tools.path())
+            .env("SCARB", &scarb)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice::<Value>(&output.stdout).unwrap()
+    };
+
+    // Not built yet
+    let report = list();
+    assert!(report["error"].is_null());
+    let packages = report["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0]["name"], "hello");
+    assert_eq!(packages[0]["version"], "0.1.0");
+    assert_eq!(packages[0]["manifest_path"], "Scarb.toml");
+    assert_eq!(packages[0]["contract_targets"], json!(["hello"]));
+    let contracts = packages[0]["contracts"].as_array().unwrap();
+    assert_eq!(contracts.len(), 2);
+    for contract in contracts {
+        assert_eq!(contract["file"], "src/lib.cairo");
+        assert!(contract["module_path"].is_string());
+        assert!(contract["kind"].is_string());
+        assert!(contract["class_hash"].is_null());
+        assert!(contract["artifact"].is_null());
+    }
+
+    // Built: the class hash comes from the artifact
+    let release = project.path().join("target/release");
+    fs::create_dir_all(&release).unwrap();
+    fs::copy(
+        ARTIFACT,
+        release.join("hello_HelloStarknet.contract_class.json"),
+    )
+    .unwrap();
+    let expected = String::from_utf8(voyager(&["hash", ARTIFACT]).stdout).unwrap();
+
+    let report = list();
+    let contracts = report["packages"][0]["contracts"].as_array().unwrap();
+    let contract = |name: &str| {
+        contracts
+            .iter()
+            .find(|contract| contract["name"] == name)
+            .unwrap()
+    };
+    assert_eq!(contract("HelloStarknet")["class_hash"], expected.trim());
+    assert_eq!(
+        contract("HelloStarknet")["artifact"],
+        "target/release/hello_HelloStarknet.contract_class.json"
+    );
+    assert!(contract("Counter")["class_hash"].is_null());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_workspace_payload_builds() {
+    let project = TempDir::new().unwrap();
+    let tools = TempDir::new().unwrap();
+    let scarb = stub_scarb(tools.path(), &workspace_project(project.path()));
+    let archive = tools.path().join("payload.zip");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_voyager"))
+        .args([
+            "verify",
+            "--dry-run",
+            "--non-interactive",
+            "--network",
+            "mainnet",
+            "--class-hash",
+            "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18",
+            "--contract-name",
+            "HelloStarknet",
+            "--output-archive",
+            &archive.display().to_string(),
+        ])
+        .current_dir(project.path())
+        .env("XDG_CONFIG_HOME", tools.path())
+        .env("SCARB", &scarb)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    // `app` inherits from the workspace manifest, which is submitted with
+    // `utils` left out of its members
+    let staged = TempDir::new().unwrap();
+    let mut zip = zip::ZipArchive::new(fs::File::open(&archive).unwrap()).unwrap();
+    zip.extract(staged.path()).unwrap();
+    for file in ["Scarb.toml", "app/Scarb.toml", "app/src/lib.cairo"] {
+        assert!(staged.path().join(file).is_file(), "{file} is not staged");
+    }
+    assert!(!staged.path().join("utils").exists());
+    let manifest: toml::Table =
+        toml::from_str(&fs::read_to_string(staged.path().join("Scarb.toml")).unwrap()).unwrap();
+    assert_eq!(
+        manifest["workspace"]["members"],
+        toml::Value::Array(vec!["app".into()])
+    );
+    assert_eq!(
+        manifest["workspace"]["package"]["version"].as_str(),
+        Some("0.1.0")
+    );
+
+    // Needs a real Scarb installation, which isn't available everywhere
+    let Ok(status) = Command::new("scarb").arg("--version").output() else {
+        eprintln!("scarb is not installed, skipping the build of the staged workspace");
+        return;
+    };
+    assert!(status.status.success());
+    let build = Command::new("scarb")
+        .args(["--release", "build"])
+        .current_dir(staged.path())
+        .output()
+        .unwrap();
+    assert!(build.status.success(), "{build:?}");
+    assert!(staged
+        .path()
+        .join("target/release/app_HelloStarknet.contract_class.json")
+        .is_file());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_precheck_refuses_toolchain_override() {
+    let project = TempDir::new().unwrap();
+    let tools = TempDir::new().unwrap();
+    let scarb = stub_scarb(tools.path(), &hello_project(project.path()));
+
+    // The stub reports Scarb 2.11.4
+    let output = Command::new(env!("CARGO_BIN_EXE_voyager"))
+        .args([
+            "verify",
+            "--dry-run",
+            "--precheck",
+            "--network",
+            "mainnet",
+            "--class-hash",
+            "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18",
+            "--contract-name",
+            "HelloStarknet",
+            "--cairo-version",
+            "2.10.1",
+        ])
+        .current_dir(project.path())
+        .env("XDG_CONFIG_HOME", tools.path())
+        .env("SCARB", &scarb)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[E064] --precheck"));
+}
+
+#[cfg(unix)]
+const CLASS_HASH: &str = "0x044dc2b3239382230d8b1e943df23b96f52eebcac93efe6e8bde92f9a2f1da18";
+
+/// Configuration pointing every built-in network at a mocked explorer, so
+/// that no request leaves the test
+#[cfg(unix)]
+fn mocked_networks(mainnet: &str, sepolia: &str, dev: &str) -> String {
+    format!(
+        "[networks.mainnet]\napi-url = \"{mainnet}\"\n\n[networks.sepolia]\napi-url = \"{sepolia}\"\n\n[networks.dev]\napi-url = \"{dev}\"\n"
+    )
+}
+
+/// Mocked explorer answering class lookups with `status` and `body`
+#[cfg(unix)]
+fn explorer(status: usize, body: &str) -> mockito::ServerGuard {
+    let mut server = mockito::Server::new();
+    server
+        .mock("GET", format!("/classes/{CLASS_HASH}").as_str())
+        .with_status(status)
+        .with_body(body)
+        .create();
+    server
+}
+
+/// Run `voyager verify --format json` on mainnet in `project`, described by
+/// `metadata`, with `config` as project configuration
+#[cfg(unix)]
+fn verify_project(
+    project: &Path,
+    metadata: &Value,
+    config: &str,
+    args: &[&str],
+) -> (Output, Value) {
+    let tools = TempDir::new().unwrap();
+    let scarb = stub_scarb(tools.path(), metadata);
+    fs::write(project.join(".voyager.toml"), config).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_voyager"))
+        .args([
+            "verify",
+            "--non-interactive",
+            "--format",
+            "json",
+            "--network",
+            "mainnet",
+        ])
+        .args(args)
+        .current_dir(project)
+        .env("XDG_CONFIG_HOME", tools.path())
+        .env("SCARB", &scarb)
+        .env_remove("VOYAGER_RPC_URL")
+        .output()
+        .unwrap();
+    let report = serde_json::from_slice(&output.stdout).unwrap();
+    (output, report)
+}
+
+/// Run `voyager verify --format json` for `HelloStarknet` of the `hello`
+/// project on mainnet, with `config` as project configuration
+#[cfg(unix)]
+fn verify_hello(config: &str, args: &[&str]) -> (Output, Value) {
+    let project = TempDir::new().unwrap();
+    let metadata = hello_project(project.path());
+    let mut args = args.to_vec();
+    args.extend(["--contract-name", "HelloStarknet"]);
+    verify_project(project.path(), &metadata, config, &args)
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_declared_class() {
+    let mut mainnet = explorer(200, r#"{"verified": false}"#);
+    let upload = mainnet
+        .mock("POST", format!("/class-verify/{CLASS_HASH}").as_str())
+        .with_body(r#"{"job_id": "job-1"}"#)
+        .create();
+    let others = explorer(404, "");
+
+    let config = mocked_networks(&mainnet.url(), &others.url(), &others.url());
+    let (output, report) = verify_hello(&config, &["--class-hash", CLASS_HASH]);
+    assert!(output.status.success(), "{output:?}");
+    upload.assert();
+    assert_eq!(report["job_id"], "job-1");
+    assert_eq!(report["already_verified"], false);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_undeclared_class() {
+    let mut mainnet = explorer(404, "");
+    let upload = mainnet
+        .mock("POST", format!("/class-verify/{CLASS_HASH}").as_str())
+        .expect(0)
+        .create();
+    let sepolia = explorer(404, "");
+    let dev = explorer(404, "");
+
+    let config = mocked_networks(&mainnet.url(), &sepolia.url(), &dev.url());
+    let (output, report) = verify_hello(&config, &["--class-hash", CLASS_HASH]);
+    assert_eq!(output.status.code(), Some(2));
+    upload.assert();
+    assert_eq!(report["error"]["code"], "E015");
+    let message = report["error"]["message"].as_str().unwrap();
+    assert!(!message.contains("The class is declared on"), "{message}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_class_declared_on_another_network() {
+    let mainnet = explorer(404, "");
+    let sepolia = explorer(200, r#"{"verified": false}"#);
+    let dev = explorer(404, "");
+
+    let config = mocked_networks(&mainnet.url(), &sepolia.url(), &dev.url());
+    let (output, report) = verify_hello(&config, &["--class-hash", CLASS_HASH]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(report["error"]["code"], "E015");
+    let message = report["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("The class is declared on: sepolia"),
+        "{message}"
+    );
+    assert!(message.contains("--network sepolia"), "{message}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_class_undeclared_on_chain() {
+    // The explorer isn't asked once the chain says the class isn't declared
+    let mut mainnet = mockito::Server::new();
+    let lookup = mainnet
+        .mock("GET", format!("/classes/{CLASS_HASH}").as_str())
+        .with_body(r#"{"verified": false}"#)
+        .expect(0)
+        .create();
+    let others = explorer(404, "");
+
+    let mut rpc = mockito::Server::new();
+    for (method, body) in [
+        (
+            "starknet_chainId",
+            json!({"jsonrpc": "2.0", "id": 1, "result": "0x534e5f4d41494e"}),
+        ),
+        (
+            "starknet_getClass",
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": 28, "message": "Class hash not found"}}),
+        ),
+    ] {
+        rpc.mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(json!({"method": method})))
+            .with_body(body.to_string())
+            .create();
+    }
+
+    let config = mocked_networks(&mainnet.url(), &others.url(), &others.url());
+    let (output, report) = verify_hello(
+        &config,
+        &["--class-hash", CLASS_HASH, "--rpc-url", &rpc.url()],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    lookup.assert();
+    assert_eq!(report["error"]["code"], "E015");
+}
+
+#[cfg(unix)]
+const ADDRESS: &str = "0x0123456789abcdef";
+
+/// Mocked explorer knowing the contract at `ADDRESS` as an instance of
+/// `class_hash`, or not at all
+#[cfg(unix)]
+fn explorer_with_contract(class_hash: Option<&str>) -> mockito::ServerGuard {
+    let mut server = mockito::Server::new();
+    let mock = server.mock("GET", format!("/contracts/{ADDRESS}").as_str());
+    match class_hash {
+        Some(class_hash) => {
+            mock.with_body(json!({"address": ADDRESS, "classHash": class_hash}).to_string())
+        }
+        None => mock.with_status(404),
+    }
+    .create();
+    server
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_address_resolved_on_chain() {
+    let mut mainnet = mockito::Server::new();
+    let lookup = mainnet
+        .mock("GET", format!("/contracts/{ADDRESS}").as_str())
+        .expect(0)
+        .create();
+    let mut rpc = mockito::Server::new();
+    rpc.mock("POST", "/")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({"method": "starknet_getClassHashAt"}),
+        ))
+        .with_body(json!({"jsonrpc": "2.0", "id": 1, "result": CLASS_HASH}).to_string())
+        .create();
+
+    let config = mocked_networks(&mainnet.url(), &mainnet.url(), &mainnet.url());
+    let (output, report) = verify_hello(
+        &config,
+        &["--dry-run", "--address", ADDRESS, "--rpc-url", &rpc.url()],
+    );
+    assert!(output.status.success(), "{output:?}");
+    lookup.assert();
+    assert_eq!(report["class_hash"], CLASS_HASH);
+    assert_eq!(report["address"], ADDRESS);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_address_resolved_on_explorer() {
+    let mainnet = explorer_with_contract(Some(CLASS_HASH));
+
+    let config = mocked_networks(&mainnet.url(), &mainnet.url(), &mainnet.url());
+    let (output, report) = verify_hello(&config, &["--dry-run", "--address", ADDRESS]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(report["class_hash"], CLASS_HASH);
+    assert_eq!(report["address"], ADDRESS);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_address_of_another_class() {
+    let mainnet = explorer_with_contract(Some(CLASS_HASH));
+
+    let config = mocked_networks(&mainnet.url(), &mainnet.url(), &mainnet.url());
+    let (output, report) = verify_hello(
+        &config,
+        &["--dry-run", "--address", ADDRESS, "--class-hash", "0x123"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(report["error"]["code"], "E058");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_unknown_address() {
+    let mainnet = explorer_with_contract(None);
+
+    let config = mocked_networks(&mainnet.url(), &mainnet.url(), &mainnet.url());
+    let (output, report) = verify_hello(&config, &["--dry-run", "--address", ADDRESS]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(report["error"]["code"], "E057");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_already_verified_class() {
+    let verified = r#"{"verified": true, "name": "HelloStarknet", "license": "MIT"}"#;
+    for (force, uploads) in [(false, 0), (true, 1)] {
+        let mut mainnet = explorer(200, verified);
+        let upload = mainnet
+            .mock("POST", format!("/class-verify/{CLASS_HASH}").as_str())
+            .with_body(r#"{"job_id": "job-1"}"#)
+            .expect(uploads)
+            .create();
+        let others = explorer(404, "");
+
+        let config = mocked_networks(&mainnet.url(), &others.url(), &others.url());
+        let mut args = vec!["--class-hash", CLASS_HASH];
+        if force {
+            args.push("--force");
+        }
+        let (output, report) = verify_hello(&config, &args);
+        assert!(output.status.success(), "{output:?}");
+        upload.assert();
+        assert_eq!(report["already_verified"], !force);
+        assert_eq!(report["class"]["verified"], true);
+        assert_eq!(
+            report["job_id"],
+            if force { json!("job-1") } else { Value::Null }
+        );
+    }
+}
+
+/// Workspace whose members `app` and `vault` both define `HelloStarknet`,
+/// `vault` also defining `Vault`
+#[cfg(unix)]
+fn contracts_workspace(dir: &Path) -> Value {
+    fs::write(
+        dir.join("Scarb.toml"),
+        "[workspace]\nmembers = [\"app\", \"vault\"]\n",
+    )
+    .unwrap();
+    let vault = format!(
+        "{CONTRACTS}\n#[starknet::contract]\nmod Vault {{\n    #[storage]\n    struct Storage {{}}\n}}\n"
+    );
+    for (name, source) in [("app", CONTRACTS), ("vault", vault.as_str())] {
+        fs::create_dir_all(dir.join(name).join("src")).unwrap();
+        fs::write(
+            dir.join(name).join("Scarb.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n[[target.starknet-contract]]\n"),
+        )
+        .unwrap();
+        fs::write(dir.join(name).join("src/lib.cairo"), source).unwrap();
+    }
+    scarb_metadata(
+        dir,
+        vec![
+            package_metadata(&dir.join("app"), "app", true),
+            package_metadata(&dir.join("vault"), "vault", true),
+        ],
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_infers_package() {
+    let project = TempDir::new().unwrap();
+    let metadata = contracts_workspace(project.path());
+    let verify = |contract_name: &str| {
+        verify_project(
+            project.path(),
+            &metadata,
+            "",
+            &[
+                "--dry-run",
+                "--class-hash",
+                CLASS_HASH,
+                "--contract-name",
+                contract_name,
+            ],
+        )
+    };
+
+    // Only `vault` defines it
+    let (output, report) = verify("Vault");
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(report["package"], "vault");
+    assert_eq!(report["files"], json!(["Scarb.toml", "src/lib.cairo"]));
+
+    // Both members define it, and --non-interactive forbids asking
+    let (output, report) = verify("HelloStarknet");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(report["error"]["code"], "E051");
+    let message = report["error"]["message"].as_str().unwrap();
+    assert!(message.contains("--package <NAME>"), "{message}");
+    assert!(message.contains("- app\n    - vault"), "{message}");
+
+    // No member defines it
+    let (output, report) = verify("Missing");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(report["error"]["code"], "E003");
+    let message = report["error"]["message"].as_str().unwrap();
+    for contract in ["HelloStarknet", "Counter", "Vault"] {
+        assert!(message.contains(contract), "{message}");
+    }
+}
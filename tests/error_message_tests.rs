@@ -4,7 +4,7 @@ use reqwest::StatusCode;
 use url::Url;
 use verifier::api::{ApiClientError, VerificationError};
 use verifier::class_hash::{ClassHash, ClassHashError};
-use verifier::errors::{MissingContract, RequestFailure};
+use verifier::errors::{MissingContract, MissingPackage, RequestFailure};
 use verifier::resolver;
 
 #[test]
@@ -39,6 +39,42 @@ fn test_missing_contract_error_with_no_available() {
     assert!(error_message.contains("Add a [tool.voyager] section"));
 }
 
+#[test]
+fn test_missing_contract_in_sources() {
+    let missing_contract = MissingContract::in_sources(
+        "Countr".to_string(),
+        vec!["Counter".to_string(), "Vault".to_string()],
+    );
+
+    let error_message = format!("{missing_contract}");
+
+    assert!(error_message.contains("[E003]"));
+    assert!(error_message.contains("not found in the package sources"));
+    assert!(error_message.contains("Did you mean 'Counter'?"));
+    assert!(error_message.contains("#[starknet::contract]"));
+    assert!(!error_message.contains("[tool.voyager]"));
+
+    let missing_contract = MissingContract::in_sources("Counter".to_string(), vec![]);
+    assert!(format!("{missing_contract}")
+        .contains("No #[starknet::contract] or #[dojo::contract] modules were found"));
+}
+
+#[test]
+fn test_missing_package_error_with_suggestions() {
+    let missing_package = MissingPackage {
+        name: "tokn".to_string(),
+        available: vec!["token".to_string(), "vault".to_string()],
+    };
+
+    let error_message = format!("{missing_package}");
+
+    assert!(error_message.contains("[E001]"));
+    assert!(error_message.contains("Package 'tokn' not found in workspace."));
+    assert!(error_message.contains("  • vault"));
+    assert!(error_message.contains("Did you mean 'token'?"));
+    assert!(error_message.contains("Use --package <name>"));
+}
+
 #[test]
 fn test_request_failure_error_with_status_specific_suggestions() {
     let url = Url::parse("https://api.example.com/verify").unwrap();
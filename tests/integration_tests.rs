@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tempfile::TempDir;
-use verifier::api::{VerificationError, VerifyJobStatus};
+use verifier::api::{VerificationError, VerificationJob, VerifyJobStatus};
 use verifier::class_hash::{ClassHash, ClassHashError};
 use verifier::resolver;
 use verifier::voyager::{self, Voyager};
@@ -50,6 +50,21 @@ fn test_verify_job_status_serialization() {
     }
 }
 
+#[test]
+fn test_verification_job_serialization_keeps_optional_fields() {
+    let raw = r#"{"job_id": "abc", "status": 4, "class_hash": "0x123", "name": "MyContract", "license": "MIT"}"#;
+    let job: VerificationJob = serde_json::from_str(raw).unwrap();
+
+    let value = serde_json::to_value(&job).unwrap();
+    assert_eq!(value["job_id"], "abc");
+    assert_eq!(value["status"], 4);
+    assert_eq!(value["name"], "MyContract");
+    assert_eq!(value["license"], "MIT");
+    // Missing optional fields are reported as null rather than omitted
+    assert!(value["dojo_version"].is_null());
+    assert!(value.as_object().unwrap().contains_key("address"));
+}
+
 #[test]
 fn test_verification_error_display() {
     let compilation_error = VerificationError::CompilationFailure("Test error".to_string());
@@ -112,6 +127,7 @@ fn test_voyager_integration() {
         Voyager {
             path: PathBuf::from("/test/path/contract1.cairo"),
             address: Some("0x123456789abcdef".to_string()),
+            class_hash: None,
         },
     );
 
@@ -120,6 +136,7 @@ fn test_voyager_integration() {
         Voyager {
             path: PathBuf::from("/test/path/contract2.cairo"),
             address: None,
+            class_hash: None,
         },
     );
 
@@ -209,7 +226,7 @@ fn test_status_display_integration() {
         "Unknown",
     ];
 
-    for (status, expected) in statuses.into_iter().zip(expected_displays.into_iter()) {
+    for (status, expected) in statuses.into_iter().zip(expected_displays) {
         assert_eq!(format!("{status}"), expected);
     }
 }